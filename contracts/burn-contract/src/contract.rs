@@ -1,17 +1,32 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Uint128,
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Reply,
+    ReplyOn, Response, StdError, StdResult, Storage, SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{
+    Cw20CoinVerified, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Denom, TokenInfoResponse,
+};
+use token_swap::msg::{
+    ExecuteMsg as SwapExecuteMsg, InfoResponse as SwapInfoResponse, QueryMsg as SwapQueryMsg,
+    TokenSelect,
 };
 
 use crate::error::ContractError;
-use crate::msg::{BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
-use crate::state::{Config, BURN_READY_TIMESTAMP, INIT_CONFIG};
+use crate::helpers::fund_community_pool_msg;
+use crate::msg::{
+    BalanceResponse, BurnStatusResponse, Cw20BalancesResponse, ExecuteMsg, InstantiateMsg,
+    OwnershipResponse, QueryMsg, SudoMsg,
+};
+use crate::state::{
+    Config, Cw20Burn, SwapPool, BURN_READY_TIMESTAMP, INIT_CONFIG, PENDING_BURN, PENDING_OWNER,
+    TOTAL_BURNED,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:burn-contract";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+const BUY_AND_BURN_REPLY_ID: u64 = 1u64;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -23,10 +38,41 @@ pub fn instantiate(
     //Store the contract name and version
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    // Validate the cw20 tokens to be burned
+    let mut cw20_tokens: Vec<Cw20Burn> = vec![];
+    for token in msg.cw20_tokens {
+        let contract_addr = validate_cw20_token(deps.as_ref(), &token.contract_addr)?;
+        if cw20_tokens.iter().any(|t| t.contract_addr == contract_addr) {
+            return Err(ContractError::DuplicateCw20Token {
+                address: token.contract_addr,
+            });
+        }
+
+        cw20_tokens.push(Cw20Burn {
+            contract_addr,
+            daily_burn_amount: Uint128::from(token.daily_burn_amount),
+        });
+    }
+
+    // Validate the optional owner
+    let owner = msg
+        .owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?;
+
+    validate_burn_window(msg.start_time, msg.end_time)?;
+
     let config = Config {
         daily_burn_amount: Uint128::from(msg.daily_burn_amount),
         burn_delay_seconds: msg.burn_delay_seconds,
         native_denom: msg.native_denom,
+        cw20_tokens,
+        swap_pools: vec![],
+        owner,
+        start_time: msg.start_time,
+        end_time: msg.end_time,
+        total_burn_cap: msg.total_burn_cap,
+        paused: false,
     };
 
     // save the owner to the INIT_CONFIG state
@@ -35,6 +81,7 @@ pub fn instantiate(
     // Set the BURN_READY_TIMESTAMP to now
     let now = _env.block.time;
     BURN_READY_TIMESTAMP.save(deps.storage, &now)?;
+    TOTAL_BURNED.save(deps.storage, &Uint128::zero())?;
 
     // return response
     Ok(Response::new().add_attribute("method", "instantiate"))
@@ -44,12 +91,255 @@ pub fn instantiate(
 pub fn execute(
     deps: DepsMut,
     env: Env,
-    _: MessageInfo,
+    info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::BurnDailyQuota {} => execute_burn_daily_quota(deps, env),
+        ExecuteMsg::Receive(msg) => execute_receive(deps, info, msg),
+        ExecuteMsg::BuyAndBurn {
+            denom,
+            amount,
+            min_output,
+        } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_buy_and_burn(deps, env, denom, amount, min_output)
+        }
+        ExecuteMsg::SetMaxDailyBurn { amount } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_set_max_daily_burn(deps, amount)
+        }
+        ExecuteMsg::WithdrawFundsToCommunityPool {
+            address,
+            denom,
+            amount,
+        } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_withdraw_funds_to_address(deps, env, address, denom, amount)
+        }
+        ExecuteMsg::FundCommunityPool { denom, amount } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_fund_community_pool(deps, env, denom, amount)
+        }
+        ExecuteMsg::SetCw20DailyBurn {
+            contract_addr,
+            amount,
+        } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_set_cw20_daily_burn(deps, contract_addr, amount)
+        }
+        ExecuteMsg::RemoveCw20Token { contract_addr } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_remove_cw20_token(deps, contract_addr)
+        }
+        ExecuteMsg::SetSwapPool {
+            denom,
+            pool_address,
+        } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_set_swap_pool(deps, denom, pool_address)
+        }
+        ExecuteMsg::RemoveSwapPool { denom } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_remove_swap_pool(deps, denom)
+        }
+        ExecuteMsg::SetBurnWindow {
+            start_time,
+            end_time,
+            total_burn_cap,
+        } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_set_burn_window(deps, start_time, end_time, total_burn_cap)
+        }
+        ExecuteMsg::SetPaused { paused } => {
+            assert_owner(deps.as_ref(), &info)?;
+            sudo_set_paused(deps, paused)
+        }
+        ExecuteMsg::TransferOwnership { new_owner } => {
+            execute_transfer_ownership(deps, info, new_owner)
+        }
+        ExecuteMsg::AcceptOwnership {} => execute_accept_ownership(deps, info),
+        ExecuteMsg::RenounceOwnership {} => execute_renounce_ownership(deps, info),
+    }
+}
+
+// Make sure the caller is the owner set in the config
+fn assert_owner(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    match config.owner {
+        Some(owner) if owner == info.sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+// The owner proposes a new owner, who then has to call AcceptOwnership
+fn execute_transfer_ownership(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    let new_owner = deps.api.addr_validate(&new_owner)?;
+    PENDING_OWNER.save(deps.storage, &new_owner)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_transfer_ownership")
+        .add_attribute("pending_owner", new_owner))
+}
+
+fn execute_accept_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending_owner = match PENDING_OWNER.may_load(deps.storage)? {
+        Some(pending_owner) => pending_owner,
+        None => return Err(ContractError::NoPendingOwner {}),
+    };
+    if pending_owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.owner = Some(pending_owner.clone());
+        Ok(data)
+    })?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_accept_ownership")
+        .add_attribute("owner", pending_owner))
+}
+
+// Leave the contract to be managed through sudo only
+fn execute_renounce_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    assert_owner(deps.as_ref(), &info)?;
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.owner = None;
+        Ok(data)
+    })?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("method", "execute_renounce_ownership"))
+}
+
+fn denom_to_string(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => denom.clone(),
+        Denom::Cw20(addr) => addr.to_string(),
+    }
+}
+
+/**
+ * Make sure the address belongs to a cw20 token contract.
+ * The burn contract burns cw20 tokens from its own balance, which every cw20 token
+ * allows, so all we need to verify is that the contract speaks the cw20 interface.
+ */
+fn validate_cw20_token(deps: Deps, address: &str) -> Result<Addr, ContractError> {
+    let contract_addr = deps.api.addr_validate(address)?;
+
+    let token_info: StdResult<TokenInfoResponse> = deps
+        .querier
+        .query_wasm_smart(&contract_addr, &Cw20QueryMsg::TokenInfo {});
+    if token_info.is_err() {
+        return Err(ContractError::InvalidCw20Token {
+            address: address.to_string(),
+        });
+    }
+
+    Ok(contract_addr)
+}
+
+fn query_cw20_balance(deps: Deps, token: &Addr, address: &Addr) -> StdResult<Uint128> {
+    let resp: cw20::BalanceResponse = deps.querier.query_wasm_smart(
+        token,
+        &Cw20QueryMsg::Balance {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(resp.balance)
+}
+
+// Only accept cw20 tokens that are configured for burning
+fn execute_receive(
+    deps: DepsMut,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+
+    let is_burned = config
+        .cw20_tokens
+        .iter()
+        .any(|token| token.contract_addr == info.sender);
+    let is_swapped = config
+        .swap_pools
+        .iter()
+        .any(|pool| pool.denom == Denom::Cw20(info.sender.clone()));
+
+    if !is_burned && !is_swapped {
+        return Err(ContractError::UnsupportedCw20Token {
+            address: info.sender.into_string(),
+        });
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_receive")
+        .add_attribute("token", info.sender)
+        .add_attribute("sender", msg.sender)
+        .add_attribute("amount", msg.amount))
+}
+
+fn validate_burn_window(
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+) -> Result<(), ContractError> {
+    if let (Some(start_time), Some(end_time)) = (start_time, end_time) {
+        if start_time >= end_time {
+            return Err(ContractError::InvalidBurnWindow {});
+        }
+    }
+
+    Ok(())
+}
+
+// Make sure the burn program is running, for the native_denom as well as the cw20 tokens
+fn assert_burn_allowed(config: &Config, now: Timestamp) -> Result<(), ContractError> {
+    if config.paused {
+        return Err(ContractError::BurnPaused {});
+    }
+
+    if let Some(start_time) = config.start_time {
+        if now < start_time {
+            return Err(ContractError::BurnNotStarted { start_time });
+        }
     }
+
+    if let Some(end_time) = config.end_time {
+        if now >= end_time {
+            return Err(ContractError::BurnEnded { end_time });
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * The amount of the native_denom that can still be burned before the total_burn_cap
+ * is reached, the cap is denominated in the native_denom and doesn't apply to cw20 tokens
+ */
+fn remaining_burn_cap(storage: &dyn Storage, config: &Config) -> StdResult<Option<Uint128>> {
+    match config.total_burn_cap {
+        Some(cap) => {
+            let total_burned = TOTAL_BURNED.may_load(storage)?.unwrap_or_default();
+            Ok(Some(cap.saturating_sub(total_burned)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn add_to_total_burned(storage: &mut dyn Storage, amount: Uint128) -> StdResult<Uint128> {
+    let total_burned = TOTAL_BURNED.may_load(storage)?.unwrap_or_default() + amount;
+    TOTAL_BURNED.save(storage, &total_burned)?;
+    Ok(total_burned)
 }
 
 fn execute_burn_daily_quota(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
@@ -57,6 +347,9 @@ fn execute_burn_daily_quota(deps: DepsMut, env: Env) -> Result<Response, Contrac
     let config = INIT_CONFIG.load(deps.storage)?;
     let now = env.block.time;
 
+    assert_burn_allowed(&config, now)?;
+    let remaining_cap = remaining_burn_cap(deps.storage, &config)?;
+
     if now < burn_ready_time {
         return Err(ContractError::DailyBurnNotReady {});
     }
@@ -72,26 +365,57 @@ fn execute_burn_daily_quota(deps: DepsMut, env: Env) -> Result<Response, Contrac
         .iter()
         .find(|coin| coin.denom == config.native_denom && !coin.amount.is_zero());
 
-    let coin = match coin {
-        Some(coin) => match coin.amount > config.daily_burn_amount {
+    // the daily quota never goes above what is left of the total_burn_cap
+    let daily_burn_amount = match remaining_cap {
+        Some(remaining_cap) => config.daily_burn_amount.min(remaining_cap),
+        None => config.daily_burn_amount,
+    };
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if let Some(coin) = coin.filter(|_| !daily_burn_amount.is_zero()) {
+        let coin = match coin.amount > daily_burn_amount {
             true => Coin {
-                amount: config.daily_burn_amount,
-                denom: config.native_denom,
+                amount: daily_burn_amount,
+                denom: config.native_denom.clone(),
             },
             false => coin.clone(),
-        },
-        None => {
-            return Err(ContractError::InsufficientContractBalance {});
+        };
+        add_to_total_burned(deps.storage, coin.amount)?;
+
+        // we can now proceed to burning the coins
+        // create a burn message
+        let amount = [coin].to_vec();
+        let burn_msg = BankMsg::Burn { amount };
+        msgs.push(burn_msg.into());
+    }
+
+    // Then we burn the daily quota of each cw20 token with a non-zero balance
+    for token in config.cw20_tokens.iter() {
+        let balance =
+            query_cw20_balance(deps.as_ref(), &token.contract_addr, &env.contract.address)?;
+        if balance.is_zero() {
+            continue;
         }
-    };
 
-    // we can now proceed to burning the coins
-    // create a burn message
-    let amount = [coin].to_vec();
-    let burn_msg = BankMsg::Burn { amount };
+        let amount = balance.min(token.daily_burn_amount);
+        msgs.push(
+            WasmMsg::Execute {
+                contract_addr: token.contract_addr.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+                funds: vec![],
+            }
+            .into(),
+        );
+    }
 
-    // Then we add the message to the response
-    let msgs: Vec<CosmosMsg> = vec![burn_msg.into()];
+    if msgs.is_empty() {
+        return match (config.total_burn_cap, remaining_cap) {
+            (Some(cap), Some(remaining_cap)) if remaining_cap.is_zero() => {
+                Err(ContractError::TotalBurnCapReached { cap })
+            }
+            _ => Err(ContractError::InsufficientContractBalance {}),
+        };
+    }
 
     // Build response
     let res = Response::new()
@@ -102,16 +426,276 @@ fn execute_burn_daily_quota(deps: DepsMut, env: Env) -> Result<Response, Contrac
     Ok(res)
 }
 
+/**
+ * Swap input_amount of the quote token for the base token using the same constant product
+ * math and 0.3% fee as the token-swap Swap {} message with input_token Quote.
+ *
+ * Because the pool charges the fee on top of the required input, we size the output
+ * for input_amount * 1000 / 1003 so that the required input never exceeds input_amount.
+ * b = Bq / (Q + q)
+ */
+fn get_swap_output_amount(
+    input_amount: Uint128,
+    base_reserve: Uint128,
+    quote_reserve: Uint128,
+) -> StdResult<Uint128> {
+    let input_amount = input_amount.multiply_ratio(1000u128, 1003u128);
+    let denominator = quote_reserve.checked_add(input_amount)?;
+    Ok(base_reserve.multiply_ratio(input_amount, denominator))
+}
+
+/**
+ * The input the token-swap pool takes for output_amount of the base token, the
+ * same math as its exact_output_variable_input
+ * q = Qb / (B - b) + 0.3%
+ */
+fn get_swap_input_amount(
+    output_amount: Uint128,
+    base_reserve: Uint128,
+    quote_reserve: Uint128,
+) -> StdResult<Uint128> {
+    let input_amount =
+        quote_reserve.multiply_ratio(output_amount, base_reserve.checked_sub(output_amount)?);
+    Ok(input_amount + input_amount.multiply_ratio(3u128, 1000u128))
+}
+
+/**
+ * The pool reserves read here can be moved by anyone earlier in the same block, so the
+ * swap of the whole input_amount must output at least the min_output chosen by the
+ * caller, and the swap is restricted to the owner and sudo
+ */
+fn sudo_buy_and_burn(
+    deps: DepsMut,
+    env: Env,
+    denom: Denom,
+    amount: Option<Uint128>,
+    min_output: Uint128,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    assert_burn_allowed(&config, env.block.time)?;
+    let remaining_cap = remaining_burn_cap(deps.storage, &config)?;
+    if let (Some(cap), Some(remaining_cap)) = (config.total_burn_cap, remaining_cap) {
+        if remaining_cap.is_zero() {
+            return Err(ContractError::TotalBurnCapReached { cap });
+        }
+    }
+
+    // Find the pool to swap denom through
+    let pool = match config.swap_pools.iter().find(|pool| pool.denom == denom) {
+        Some(pool) => pool.clone(),
+        None => {
+            return Err(ContractError::NoSwapPoolForDenom {
+                denom: denom_to_string(&denom),
+            });
+        }
+    };
+
+    // Get the amount of denom to swap, which defaults to the whole contract balance
+    let balance = match &denom {
+        Denom::Native(native) => {
+            deps.querier
+                .query_balance(&env.contract.address, native)?
+                .amount
+        }
+        Denom::Cw20(addr) => query_cw20_balance(deps.as_ref(), addr, &env.contract.address)?,
+    };
+    let input_amount = amount.unwrap_or(balance);
+    if input_amount.is_zero() || input_amount > balance {
+        return Err(ContractError::InsufficientContractBalance {});
+    }
+
+    // Price the swap from the pool reserves
+    let info: SwapInfoResponse = deps
+        .querier
+        .query_wasm_smart(&pool.pool_addr, &SwapQueryMsg::Info {})?;
+    let output_amount =
+        get_swap_output_amount(input_amount, info.base_reserve, info.quote_reserve)?;
+    if output_amount.is_zero() || output_amount < min_output {
+        return Err(ContractError::InsufficientSwapOutput {
+            min_output,
+            output: output_amount,
+        });
+    }
+
+    // Never buy more than what is left of the total_burn_cap, and only spend
+    // the input the pool requires for that output
+    let output_amount = match remaining_cap {
+        Some(remaining_cap) => output_amount.min(remaining_cap),
+        None => output_amount,
+    };
+    let input_amount = get_swap_input_amount(output_amount, info.base_reserve, info.quote_reserve)?
+        .min(input_amount);
+
+    // Save the proceeds so they can be burned once the swap succeeds
+    PENDING_BURN.save(
+        deps.storage,
+        &Coin {
+            denom: config.native_denom,
+            amount: output_amount,
+        },
+    )?;
+
+    // The pool pulls exactly input_amount of cw20 input tokens with TransferFrom,
+    // while native input tokens are sent along with the swap
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let funds = match &denom {
+        Denom::Native(native) => vec![Coin {
+            denom: native.clone(),
+            amount: input_amount,
+        }],
+        Denom::Cw20(addr) => {
+            msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+                        spender: pool.pool_addr.to_string(),
+                        amount: input_amount,
+                        expires: None,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            );
+            vec![]
+        }
+    };
+
+    let sub_msg = SubMsg {
+        gas_limit: None,
+        id: BUY_AND_BURN_REPLY_ID,
+        reply_on: ReplyOn::Success,
+        msg: WasmMsg::Execute {
+            contract_addr: pool.pool_addr.to_string(),
+            msg: to_binary(&SwapExecuteMsg::Swap {
+                input_token: TokenSelect::Quote,
+                input_amount,
+                output_amount,
+                expiration: None,
+            })?,
+            funds,
+        }
+        .into(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_buy_and_burn")
+        .add_attribute("input_denom", denom_to_string(&denom))
+        .add_attribute("input_amount", input_amount)
+        .add_attribute("output_amount", output_amount)
+        .add_messages(msgs)
+        .add_submessage(sub_msg))
+}
+
+/**
+ * Handle reply for execute_buy_and_burn
+ * Load the PENDING_BURN coins received from the swap and burn them
+ */
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        BUY_AND_BURN_REPLY_ID => handle_buy_and_burn_reply(deps),
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
+    }
+}
+
+fn handle_buy_and_burn_reply(deps: DepsMut) -> StdResult<Response> {
+    let coin = PENDING_BURN.load(deps.storage)?;
+    PENDING_BURN.remove(deps.storage);
+    add_to_total_burned(deps.storage, coin.amount)?;
+
+    Ok(Response::new()
+        .add_attribute("burned_amount", coin.amount)
+        .add_message(BankMsg::Burn { amount: vec![coin] }))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
     match msg {
         SudoMsg::SetMaxDailyBurn { amount } => sudo_set_max_daily_burn(deps, amount),
-        SudoMsg::WithdrawFundsToCommunityPool { address } => {
-            sudo_withdraw_funds_to_address(deps, env, address)
+        SudoMsg::WithdrawFundsToCommunityPool {
+            address,
+            denom,
+            amount,
+        } => sudo_withdraw_funds_to_address(deps, env, address, denom, amount),
+        SudoMsg::FundCommunityPool { denom, amount } => {
+            sudo_fund_community_pool(deps, env, denom, amount)
         }
+        SudoMsg::SetCw20DailyBurn {
+            contract_addr,
+            amount,
+        } => sudo_set_cw20_daily_burn(deps, contract_addr, amount),
+        SudoMsg::RemoveCw20Token { contract_addr } => sudo_remove_cw20_token(deps, contract_addr),
+        SudoMsg::SetSwapPool {
+            denom,
+            pool_address,
+        } => sudo_set_swap_pool(deps, denom, pool_address),
+        SudoMsg::RemoveSwapPool { denom } => sudo_remove_swap_pool(deps, denom),
+        SudoMsg::BuyAndBurn {
+            denom,
+            amount,
+            min_output,
+        } => sudo_buy_and_burn(deps, env, denom, amount, min_output),
+        SudoMsg::SetOwner { owner } => sudo_set_owner(deps, owner),
+        SudoMsg::SetBurnWindow {
+            start_time,
+            end_time,
+            total_burn_cap,
+        } => sudo_set_burn_window(deps, start_time, end_time, total_burn_cap),
+        SudoMsg::SetPaused { paused } => sudo_set_paused(deps, paused),
     }
 }
 
+fn sudo_set_burn_window(
+    deps: DepsMut,
+    start_time: Option<Timestamp>,
+    end_time: Option<Timestamp>,
+    total_burn_cap: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    validate_burn_window(start_time, end_time)?;
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.start_time = start_time;
+        data.end_time = end_time;
+        data.total_burn_cap = total_burn_cap;
+        Ok(data)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "sudo_set_burn_window"))
+}
+
+fn sudo_set_paused(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.paused = paused;
+        Ok(data)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+// Governance can always set or remove the owner, which also cancels any pending transfer
+fn sudo_set_owner(deps: DepsMut, owner: Option<String>) -> Result<Response, ContractError> {
+    let owner = owner
+        .map(|owner| deps.api.addr_validate(&owner))
+        .transpose()?;
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.owner = owner.clone();
+        Ok(data)
+    })?;
+    PENDING_OWNER.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_set_owner")
+        .add_attribute(
+            "owner",
+            owner
+                .map(String::from)
+                .unwrap_or_else(|| "none".to_string()),
+        ))
+}
+
 fn sudo_set_max_daily_burn(deps: DepsMut, amount: u128) -> Result<Response, ContractError> {
     // Here we update the owner in the config
     let updated_config = INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
@@ -125,31 +709,156 @@ fn sudo_set_max_daily_burn(deps: DepsMut, amount: u128) -> Result<Response, Cont
         .add_attribute("daily_burn_amount", updated_config.daily_burn_amount))
 }
 
+// Add a new cw20 token to be burned or update the daily_burn_amount of an existing one
+fn sudo_set_cw20_daily_burn(
+    deps: DepsMut,
+    contract_addr: String,
+    amount: u128,
+) -> Result<Response, ContractError> {
+    let contract_addr = validate_cw20_token(deps.as_ref(), &contract_addr)?;
+    let daily_burn_amount = Uint128::from(amount);
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        match data
+            .cw20_tokens
+            .iter_mut()
+            .find(|token| token.contract_addr == contract_addr)
+        {
+            Some(token) => token.daily_burn_amount = daily_burn_amount,
+            None => data.cw20_tokens.push(Cw20Burn {
+                contract_addr: contract_addr.clone(),
+                daily_burn_amount,
+            }),
+        }
+
+        Ok(data)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_set_cw20_daily_burn")
+        .add_attribute("contract_addr", contract_addr)
+        .add_attribute("daily_burn_amount", daily_burn_amount))
+}
+
+fn sudo_remove_cw20_token(deps: DepsMut, contract_addr: String) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+
+    let len = config.cw20_tokens.len();
+    config
+        .cw20_tokens
+        .retain(|token| token.contract_addr != contract_addr);
+    if config.cw20_tokens.len() == len {
+        return Err(ContractError::UnsupportedCw20Token {
+            address: contract_addr,
+        });
+    }
+
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_remove_cw20_token")
+        .add_attribute("contract_addr", contract_addr))
+}
+
+/**
+ * Add or replace the token-swap pool used to buy and burn denom.
+ * The pool must quote denom against the native_denom as its base token
+ */
+fn sudo_set_swap_pool(
+    deps: DepsMut,
+    denom: Denom,
+    pool_address: String,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    let pool_addr = deps.api.addr_validate(&pool_address)?;
+
+    let info: StdResult<SwapInfoResponse> = deps
+        .querier
+        .query_wasm_smart(&pool_addr, &SwapQueryMsg::Info {});
+    match info {
+        Ok(info)
+            if info.base_denom == Denom::Native(config.native_denom.clone())
+                && info.quote_denom == denom => {}
+        _ => {
+            return Err(ContractError::InvalidSwapPool {
+                address: pool_address,
+            });
+        }
+    }
+
+    INIT_CONFIG.update(deps.storage, |mut data| -> StdResult<_> {
+        data.swap_pools.retain(|pool| pool.denom != denom);
+        data.swap_pools.push(SwapPool {
+            denom: denom.clone(),
+            pool_addr: pool_addr.clone(),
+        });
+
+        Ok(data)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_set_swap_pool")
+        .add_attribute("denom", denom_to_string(&denom))
+        .add_attribute("pool_addr", pool_addr))
+}
+
+fn sudo_remove_swap_pool(deps: DepsMut, denom: Denom) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+
+    let len = config.swap_pools.len();
+    config.swap_pools.retain(|pool| pool.denom != denom);
+    if config.swap_pools.len() == len {
+        return Err(ContractError::NoSwapPoolForDenom {
+            denom: denom_to_string(&denom),
+        });
+    }
+
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_remove_swap_pool")
+        .add_attribute("denom", denom_to_string(&denom)))
+}
+
+/**
+ * Get the coin to withdraw from the contract balance.
+ * The denom defaults to the native_denom and the amount defaults to the whole balance
+ */
+fn get_withdraw_coin(
+    deps: Deps,
+    env: &Env,
+    denom: Option<String>,
+    amount: Option<Uint128>,
+) -> Result<Coin, ContractError> {
+    let denom = match denom {
+        Some(denom) => denom,
+        None => INIT_CONFIG.load(deps.storage)?.native_denom,
+    };
+
+    let balance = deps.querier.query_balance(&env.contract.address, &denom)?;
+    let amount = amount.unwrap_or(balance.amount);
+    if amount.is_zero() || amount > balance.amount {
+        return Err(ContractError::InsufficientContractBalance {});
+    }
+
+    Ok(Coin { denom, amount })
+}
+
 fn sudo_withdraw_funds_to_address(
     deps: DepsMut,
     env: Env,
     address: String,
+    denom: Option<String>,
+    amount: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    let config = INIT_CONFIG.load(deps.storage)?;
-
     // we validate the address
     let address = deps.api.addr_validate(&address)?;
 
-    // find the coin with non-zero balance that matches the denom
-    let contract_balances = deps.querier.query_all_balances(&env.contract.address)?;
-    let coin = contract_balances
-        .iter()
-        .find(|coin| coin.denom == config.native_denom && !coin.amount.is_zero());
-
-    let coin = match coin {
-        Some(coin) => coin.clone(),
-        None => {
-            return Err(ContractError::InsufficientContractBalance {});
-        }
-    };
+    // get the coin to withdraw
+    let coin = get_withdraw_coin(deps.as_ref(), &env, denom, amount)?;
 
     // we can now proceed to transfering the contract balance to the provided address
-    // create a burn message
+    // create a send message
     let amount = [coin].to_vec();
     let send_msg = BankMsg::Send {
         amount,
@@ -168,12 +877,31 @@ fn sudo_withdraw_funds_to_address(
     Ok(res)
 }
 
+// Deposit the funds into the x/distribution community pool
+fn sudo_fund_community_pool(
+    deps: DepsMut,
+    env: Env,
+    denom: Option<String>,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let coin = get_withdraw_coin(deps.as_ref(), &env, denom, amount)?;
+    let fund_msg = fund_community_pool_msg(&env.contract.address, std::slice::from_ref(&coin));
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_fund_community_pool")
+        .add_attribute("amount", coin.to_string())
+        .add_message(fund_msg))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     // Match and route the query message to the appropriate handler
     match msg {
         QueryMsg::Balance {} => to_binary(&query_balance(deps, env)?),
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Cw20Balances {} => to_binary(&query_cw20_balances(deps, env)?),
+        QueryMsg::Ownership {} => to_binary(&query_ownership(deps)?),
+        QueryMsg::BurnStatus {} => to_binary(&query_burn_status(deps)?),
     }
 }
 
@@ -200,6 +928,43 @@ fn query_balance(deps: Deps, env: Env) -> StdResult<BalanceResponse> {
     })
 }
 
+fn query_cw20_balances(deps: Deps, env: Env) -> StdResult<Cw20BalancesResponse> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+
+    let balances = config
+        .cw20_tokens
+        .into_iter()
+        .map(|token| {
+            let amount = query_cw20_balance(deps, &token.contract_addr, &env.contract.address)?;
+            Ok(Cw20CoinVerified {
+                address: token.contract_addr,
+                amount,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Cw20BalancesResponse { balances })
+}
+
+fn query_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+
+    Ok(OwnershipResponse {
+        owner: config.owner,
+        pending_owner: PENDING_OWNER.may_load(deps.storage)?,
+    })
+}
+
+fn query_burn_status(deps: Deps) -> StdResult<BurnStatusResponse> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+
+    Ok(BurnStatusResponse {
+        total_burned: TOTAL_BURNED.may_load(deps.storage)?.unwrap_or_default(),
+        burn_ready_timestamp: BURN_READY_TIMESTAMP.load(deps.storage)?,
+        paused: config.paused,
+    })
+}
+
 fn query_config(deps: Deps) -> StdResult<Config> {
     let config = INIT_CONFIG.load(deps.storage)?;
     Ok(config)
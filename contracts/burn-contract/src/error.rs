@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -15,6 +15,45 @@ pub enum ContractError {
     #[error("Contract balance is too small to execute")]
     InsufficientContractBalance {},
 
+    #[error("Address {address:?} is not a valid cw20 token contract")]
+    InvalidCw20Token { address: String },
+
+    #[error("Cw20 token {address:?} is listed more than once")]
+    DuplicateCw20Token { address: String },
+
+    #[error("Cw20 token {address:?} is not configured for burning")]
+    UnsupportedCw20Token { address: String },
+
+    #[error("Address {address:?} is not a token-swap pool for the configured denoms")]
+    InvalidSwapPool { address: String },
+
+    #[error("No swap pool is configured for denom {denom:?}")]
+    NoSwapPoolForDenom { denom: String },
+
+    #[error("Swap output is below min_output: min_output: {min_output}, output: {output}")]
+    InsufficientSwapOutput {
+        min_output: Uint128,
+        output: Uint128,
+    },
+
+    #[error("There is no pending ownership transfer")]
+    NoPendingOwner {},
+
+    #[error("Burning is paused")]
+    BurnPaused {},
+
+    #[error("Burning starts at {start_time}")]
+    BurnNotStarted { start_time: Timestamp },
+
+    #[error("Burning ended at {end_time}")]
+    BurnEnded { end_time: Timestamp },
+
+    #[error("Total burn cap of {cap} has been reached")]
+    TotalBurnCapReached { cap: Uint128 },
+
+    #[error("Burn window start_time must be before end_time")]
+    InvalidBurnWindow {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{to_binary, Addr, CosmosMsg, StdResult, WasmMsg};
+use cosmwasm_std::{to_binary, Addr, Binary, Coin, CosmosMsg, StdResult, WasmMsg};
+use proto_encoding::encode_bytes_field;
 
 use crate::msg::ExecuteMsg;
 
@@ -25,3 +26,28 @@ impl CwTemplateContract {
         .into())
     }
 }
+
+// The Stargate type url of the x/distribution message that deposits into the community pool
+pub const FUND_COMMUNITY_POOL_TYPE_URL: &str = "/cosmos.distribution.v1beta1.MsgFundCommunityPool";
+
+/// Build a MsgFundCommunityPool stargate message, cosmwasm-std only supports
+/// DistributionMsg::FundCommunityPool from 1.3 onwards
+pub fn fund_community_pool_msg(depositor: &Addr, amount: &[Coin]) -> CosmosMsg {
+    // message MsgFundCommunityPool {
+    //   repeated cosmos.base.v1beta1.Coin amount = 1;
+    //   string depositor = 2;
+    // }
+    let mut value = vec![];
+    for coin in amount {
+        let mut encoded_coin = vec![];
+        encode_bytes_field(&mut encoded_coin, 1, coin.denom.as_bytes());
+        encode_bytes_field(&mut encoded_coin, 2, coin.amount.to_string().as_bytes());
+        encode_bytes_field(&mut value, 1, &encoded_coin);
+    }
+    encode_bytes_field(&mut value, 2, depositor.as_bytes());
+
+    CosmosMsg::Stargate {
+        type_url: FUND_COMMUNITY_POOL_TYPE_URL.to_string(),
+        value: Binary::from(value),
+    }
+}
@@ -1,10 +1,18 @@
 #[cfg(test)]
 mod tests {
     use crate::helpers::CwTemplateContract;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
-    use crate::state::Config;
+    use crate::msg::{
+        BurnStatusResponse, Cw20BalancesResponse, Cw20BurnInfo, ExecuteMsg, InstantiateMsg,
+        QueryMsg, SudoMsg,
+    };
+    use crate::state::{Config, Cw20Burn, SwapPool};
+    use crate::ContractError;
 
     use cosmwasm_std::{Addr, BalanceResponse, BlockInfo, Coin, Empty, Uint128};
+    use cw20::{
+        AllowanceResponse, Cw20Coin, Cw20CoinVerified, Cw20Contract, Cw20ExecuteMsg, Cw20QueryMsg,
+        Denom, TokenInfoResponse,
+    };
     use cw_multi_test::{App, AppBuilder, AppResponse, Contract, ContractWrapper, Executor};
 
     pub fn contract_template() -> Box<dyn Contract<Empty>> {
@@ -13,14 +21,40 @@ mod tests {
             crate::contract::instantiate,
             crate::contract::query,
         );
-        let contract_with_sudo = contract.with_sudo(crate::contract::sudo);
+        let contract_with_sudo = contract
+            .with_sudo(crate::contract::sudo)
+            .with_reply(crate::contract::reply);
         Box::new(contract_with_sudo)
     }
 
+    fn contract_token_swap() -> Box<dyn Contract<Empty>> {
+        Box::new(
+            ContractWrapper::new(
+                token_swap::contract::execute,
+                token_swap::contract::instantiate,
+                token_swap::contract::query,
+            )
+            .with_reply(token_swap::contract::reply),
+        )
+    }
+
+    fn contract_cw20() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        ))
+    }
+
     const NATIVE_DENOM: &str = "udenom";
     const DEFAULT_DAILY_QUOTA: u128 = 500_000_000_000_000u128;
     const BURN_DELAY_SECONDS: u64 = 86400u64;
     const USER: &str = "user";
+    const OWNER: &str = "owner";
+    const CW20_SUPPLY: u128 = 1_000_000u128;
+    const CW20_DAILY_QUOTA: u128 = 300_000u128;
+    const IBC_DENOM: &str = "ibc/denom1";
+    const POOL_LIQUIDITY: u128 = 500_000u128;
 
     // Here we create a struct for instatation config
     struct InstantiationResponse {
@@ -37,17 +71,113 @@ mod tests {
                     storage,
                     &Addr::unchecked(USER),
                     // this amount denote the chain total supply
-                    vec![Coin {
-                        denom: NATIVE_DENOM.to_string(),
-                        amount: Uint128::from(DEFAULT_DAILY_QUOTA * 100),
-                    }],
+                    vec![
+                        Coin {
+                            denom: NATIVE_DENOM.to_string(),
+                            amount: Uint128::from(DEFAULT_DAILY_QUOTA * 100),
+                        },
+                        Coin {
+                            denom: IBC_DENOM.to_string(),
+                            amount: Uint128::from(DEFAULT_DAILY_QUOTA),
+                        },
+                    ],
                 )
                 .unwrap();
         })
     }
 
+    // Create a new cw20 token with CW20_SUPPLY belonging to USER
+    fn create_cw20_token(app: &mut App) -> Cw20Contract {
+        let cw20_id = app.store_code(contract_cw20());
+        let msg = cw20_base::msg::InstantiateMsg {
+            name: "Cw20 Token".to_string(),
+            symbol: "CWTOKEN".to_string(),
+            decimals: 6,
+            initial_balances: vec![Cw20Coin {
+                address: USER.to_string(),
+                amount: Uint128::from(CW20_SUPPLY),
+            }],
+            mint: None,
+            marketing: None,
+        };
+        let addr = app
+            .instantiate_contract(cw20_id, Addr::unchecked(USER), &msg, &[], "CWTOKEN", None)
+            .unwrap();
+        Cw20Contract(addr)
+    }
+
+    // Create a token-swap pool of NATIVE_DENOM against quote_denom with POOL_LIQUIDITY of each
+    fn create_swap_pool(app: &mut App, quote_denom: Denom) -> Addr {
+        let swap_id = app.store_code(contract_token_swap());
+        let lp_token_id = app.store_code(contract_cw20());
+        let msg = token_swap::msg::InstantiateMsg {
+            native_denom: Denom::Native(NATIVE_DENOM.to_string()),
+            base_denom: Denom::Native(NATIVE_DENOM.to_string()),
+            quote_denom: quote_denom.clone(),
+            lp_token_code_id: lp_token_id,
+        };
+        let pool_addr = app
+            .instantiate_contract(swap_id, Addr::unchecked(USER), &msg, &[], "pool", None)
+            .unwrap();
+
+        let mut funds = vec![Coin {
+            denom: NATIVE_DENOM.to_string(),
+            amount: Uint128::from(POOL_LIQUIDITY),
+        }];
+        match quote_denom {
+            Denom::Native(denom) => funds.push(Coin {
+                denom,
+                amount: Uint128::from(POOL_LIQUIDITY),
+            }),
+            Denom::Cw20(addr) => {
+                let msg = Cw20ExecuteMsg::IncreaseAllowance {
+                    spender: pool_addr.to_string(),
+                    amount: Uint128::from(POOL_LIQUIDITY),
+                    expires: None,
+                };
+                app.execute_contract(Addr::unchecked(USER), addr, &msg, &[])
+                    .unwrap();
+            }
+        }
+
+        let msg = token_swap::msg::ExecuteMsg::AddLiquidity {
+            base_token_amount: Uint128::from(POOL_LIQUIDITY),
+            max_quote_token_amount: Uint128::from(POOL_LIQUIDITY),
+            expiration: None,
+        };
+        app.execute_contract(Addr::unchecked(USER), pool_addr.clone(), &msg, &funds)
+            .unwrap();
+
+        pool_addr
+    }
+
+    fn get_pool_info(app: &mut App, pool_addr: Addr) -> token_swap::msg::InfoResponse {
+        let msg = token_swap::msg::QueryMsg::Info {};
+        app.wrap().query_wasm_smart(pool_addr, &msg).unwrap()
+    }
+
+    fn execute_sudo_set_swap_pool(
+        app: &mut App,
+        c_address: Addr,
+        denom: Denom,
+        pool_addr: &Addr,
+    ) -> anyhow::Result<AppResponse> {
+        let msg = SudoMsg::SetSwapPool {
+            denom,
+            pool_address: pool_addr.to_string(),
+        };
+        app.wasm_sudo(c_address, &msg)
+    }
+
     fn mock_instantiate(funds: &[Coin]) -> InstantiationResponse {
-        let mut app = mock_app();
+        mock_instantiate_with_app(mock_app(), funds, vec![])
+    }
+
+    fn mock_instantiate_with_app(
+        mut app: App,
+        funds: &[Coin],
+        cw20_tokens: Vec<Cw20BurnInfo>,
+    ) -> InstantiationResponse {
         let cw_template_id = app.store_code(contract_template());
         let burn_delay_seconds = BURN_DELAY_SECONDS;
         let daily_burn_amount = DEFAULT_DAILY_QUOTA;
@@ -56,6 +186,11 @@ mod tests {
             burn_delay_seconds,
             daily_burn_amount,
             native_denom: String::from(NATIVE_DENOM),
+            cw20_tokens,
+            owner: Some(String::from(OWNER)),
+            start_time: None,
+            end_time: None,
+            total_burn_cap: None,
         };
 
         let cw_template_contract_addr = app
@@ -99,6 +234,13 @@ mod tests {
         result
     }
 
+    fn get_cw20_balances(app: &mut App, contract_address: Addr) -> Cw20BalancesResponse {
+        let msg = QueryMsg::Cw20Balances {};
+        let result: Cw20BalancesResponse =
+            app.wrap().query_wasm_smart(contract_address, &msg).unwrap();
+        result
+    }
+
     fn execute_sudo_set_max_daily_burn(
         app: &mut App,
         c_address: Addr,
@@ -113,7 +255,11 @@ mod tests {
         contract_address: Addr,
         address: String,
     ) -> anyhow::Result<AppResponse> {
-        let msg = SudoMsg::WithdrawFundsToCommunityPool { address };
+        let msg = SudoMsg::WithdrawFundsToCommunityPool {
+            address,
+            denom: None,
+            amount: None,
+        };
         app.wasm_sudo(contract_address, &msg)
     }
 
@@ -222,4 +368,566 @@ mod tests {
         let cosmos_msg = instance.c_template.call(msg).unwrap();
         instance.app.execute(sender, cosmos_msg).unwrap_err();
     }
+
+    #[test]
+    fn execute_burn_daily_quota_with_cw20_token() {
+        let mut app = mock_app();
+        let token = create_cw20_token(&mut app);
+        let mut instance = mock_instantiate_with_app(
+            app,
+            &[],
+            vec![Cw20BurnInfo {
+                contract_addr: token.addr().to_string(),
+                daily_burn_amount: CW20_DAILY_QUOTA,
+            }],
+        );
+
+        // verify that the cw20 token is saved to the config
+        let config = get_config(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(
+            config.cw20_tokens,
+            vec![Cw20Burn {
+                contract_addr: token.addr(),
+                daily_burn_amount: Uint128::from(CW20_DAILY_QUOTA),
+            }]
+        );
+
+        // fund the contract through the Receive hook and with a plain transfer
+        let sender = Addr::unchecked(USER);
+        let msg = Cw20ExecuteMsg::Send {
+            contract: instance.c_addr.to_string(),
+            amount: Uint128::from(200_000u128),
+            msg: Default::default(),
+        };
+        instance
+            .app
+            .execute(sender.clone(), token.call(msg).unwrap())
+            .unwrap();
+
+        let msg = Cw20ExecuteMsg::Transfer {
+            recipient: instance.c_addr.to_string(),
+            amount: Uint128::from(200_000u128),
+        };
+        instance
+            .app
+            .execute(sender.clone(), token.call(msg).unwrap())
+            .unwrap();
+
+        let balances = get_cw20_balances(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(
+            balances.balances,
+            vec![Cw20CoinVerified {
+                address: token.addr(),
+                amount: Uint128::from(400_000u128),
+            }]
+        );
+
+        // the daily quota is burned and removed from the total supply
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance.app.execute(sender.clone(), cosmos_msg).unwrap();
+
+        let balances = get_cw20_balances(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(balances.balances[0].amount, Uint128::from(100_000u128));
+
+        let token_info: TokenInfoResponse = instance
+            .app
+            .wrap()
+            .query_wasm_smart(token.addr(), &Cw20QueryMsg::TokenInfo {})
+            .unwrap();
+        assert_eq!(
+            token_info.total_supply,
+            Uint128::from(CW20_SUPPLY - CW20_DAILY_QUOTA)
+        );
+
+        // after the delay, the rest of the balance is burned
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance.app.update_block(advance_one_hour_after_delay);
+        instance.app.execute(sender, cosmos_msg).unwrap();
+
+        let balances = get_cw20_balances(&mut instance.app, instance.c_addr);
+        assert_eq!(balances.balances[0].amount, Uint128::zero());
+    }
+
+    #[test]
+    fn cw20_burn_continues_after_total_burn_cap() {
+        let mut app = mock_app();
+        let token = create_cw20_token(&mut app);
+        let funds = [Coin {
+            denom: String::from(NATIVE_DENOM),
+            amount: Uint128::from(DEFAULT_DAILY_QUOTA),
+        }];
+        let mut instance = mock_instantiate_with_app(
+            app,
+            &funds,
+            vec![Cw20BurnInfo {
+                contract_addr: token.addr().to_string(),
+                daily_burn_amount: CW20_DAILY_QUOTA,
+            }],
+        );
+
+        let msg = Cw20ExecuteMsg::Transfer {
+            recipient: instance.c_addr.to_string(),
+            amount: Uint128::from(400_000u128),
+        };
+        instance
+            .app
+            .execute(Addr::unchecked(USER), token.call(msg).unwrap())
+            .unwrap();
+
+        // the cap only leaves half of the native daily quota to be burned
+        let total_burn_cap = Uint128::from(DEFAULT_DAILY_QUOTA / 2);
+        instance
+            .app
+            .wasm_sudo(
+                instance.c_addr.clone(),
+                &SudoMsg::SetBurnWindow {
+                    start_time: None,
+                    end_time: None,
+                    total_burn_cap: Some(total_burn_cap),
+                },
+            )
+            .unwrap();
+
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance
+            .app
+            .execute(Addr::unchecked(USER), cosmos_msg.clone())
+            .unwrap();
+        let contract_balance = get_balance(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(
+            contract_balance.amount.amount,
+            Uint128::from(DEFAULT_DAILY_QUOTA) - total_burn_cap
+        );
+
+        // once the cap is reached, the cw20 tokens are still burned
+        instance.app.update_block(advance_one_hour_after_delay);
+        instance
+            .app
+            .execute(Addr::unchecked(USER), cosmos_msg.clone())
+            .unwrap();
+        let contract_balance = get_balance(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(
+            contract_balance.amount.amount,
+            Uint128::from(DEFAULT_DAILY_QUOTA) - total_burn_cap
+        );
+        let balances = get_cw20_balances(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(balances.balances[0].amount, Uint128::zero());
+
+        // and only with nothing left to burn the cap is reported
+        instance.app.update_block(advance_one_hour_after_delay);
+        let _err = instance
+            .app
+            .execute(Addr::unchecked(USER), cosmos_msg)
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::TotalBurnCapReached {
+                cap: total_burn_cap
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn receive_unsupported_cw20_token_fails() {
+        let mut app = mock_app();
+        let token = create_cw20_token(&mut app);
+        let mut instance = mock_instantiate_with_app(app, &[], vec![]);
+
+        // the token is not configured, so sending it to the contract is refused
+        let msg = Cw20ExecuteMsg::Send {
+            contract: instance.c_addr.to_string(),
+            amount: Uint128::from(200_000u128),
+            msg: Default::default(),
+        };
+        instance
+            .app
+            .execute(Addr::unchecked(USER), token.call(msg).unwrap())
+            .unwrap_err();
+
+        // once it is added through sudo, the same Send succeeds
+        let msg = SudoMsg::SetCw20DailyBurn {
+            contract_addr: token.addr().to_string(),
+            amount: CW20_DAILY_QUOTA,
+        };
+        instance
+            .app
+            .wasm_sudo(instance.c_addr.clone(), &msg)
+            .unwrap();
+
+        let msg = Cw20ExecuteMsg::Send {
+            contract: instance.c_addr.to_string(),
+            amount: Uint128::from(200_000u128),
+            msg: Default::default(),
+        };
+        instance
+            .app
+            .execute(Addr::unchecked(USER), token.call(msg).unwrap())
+            .unwrap();
+
+        let balances = get_cw20_balances(&mut instance.app, instance.c_addr);
+        assert_eq!(balances.balances[0].amount, Uint128::from(200_000u128));
+    }
+
+    #[test]
+    fn buy_and_burn_with_native_quote() {
+        let mut app = mock_app();
+        let pool_addr = create_swap_pool(&mut app, Denom::Native(IBC_DENOM.to_string()));
+        let funds = [Coin {
+            denom: String::from(IBC_DENOM),
+            amount: Uint128::from(10_000u128),
+        }];
+        let mut instance = mock_instantiate_with_app(app, &funds, vec![]);
+
+        let denom = Denom::Native(IBC_DENOM.to_string());
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr.clone(),
+            denom.clone(),
+            &pool_addr,
+        )
+        .unwrap();
+
+        let config = get_config(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(
+            config.swap_pools,
+            vec![SwapPool {
+                denom: denom.clone(),
+                pool_addr: pool_addr.clone(),
+            }]
+        );
+
+        // swap the whole ibc balance and burn the proceeds
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom,
+            amount: None,
+            min_output: Uint128::from(9_700u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap();
+
+        // 10_000 * 1000 / 1003 = 9970 input, 500_000 * 9970 / 509_970 = 9775 output
+        let info = get_pool_info(&mut instance.app, pool_addr);
+        assert_eq!(info.base_reserve, Uint128::from(POOL_LIQUIDITY - 9775));
+
+        // nothing is left in the contract except the change returned by the pool
+        let contract_balance = get_balance(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(contract_balance.amount.amount, Uint128::zero());
+
+        let ibc_balance = instance
+            .app
+            .wrap()
+            .query_balance(instance.c_addr, IBC_DENOM)
+            .unwrap();
+        assert_eq!(
+            ibc_balance.amount,
+            Uint128::from(10_000u128 + POOL_LIQUIDITY) - info.quote_reserve
+        );
+    }
+
+    #[test]
+    fn buy_and_burn_with_cw20_quote() {
+        let mut app = mock_app();
+        let token = create_cw20_token(&mut app);
+        let pool_addr = create_swap_pool(&mut app, Denom::Cw20(token.addr()));
+        let mut instance = mock_instantiate_with_app(app, &[], vec![]);
+
+        let denom = Denom::Cw20(token.addr());
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr.clone(),
+            denom.clone(),
+            &pool_addr,
+        )
+        .unwrap();
+
+        // tokens with a swap pool can be sent through the Receive hook
+        let msg = Cw20ExecuteMsg::Send {
+            contract: instance.c_addr.to_string(),
+            amount: Uint128::from(10_000u128),
+            msg: Default::default(),
+        };
+        instance
+            .app
+            .execute(Addr::unchecked(USER), token.call(msg).unwrap())
+            .unwrap();
+
+        // swap part of the balance and burn the proceeds
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom,
+            amount: Some(Uint128::from(5_000u128)),
+            min_output: Uint128::from(4_000u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap();
+
+        let info = get_pool_info(&mut instance.app, pool_addr.clone());
+        assert!(info.base_reserve < Uint128::from(POOL_LIQUIDITY));
+
+        let contract_balance = get_balance(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(contract_balance.amount.amount, Uint128::zero());
+
+        // only the tokens required by the pool were taken and approved
+        let balance = token
+            .balance::<_, _, Empty>(&instance.app, instance.c_addr.clone())
+            .unwrap();
+        assert_eq!(
+            balance,
+            Uint128::from(10_000u128 + POOL_LIQUIDITY) - info.quote_reserve
+        );
+        let allowance: AllowanceResponse = instance
+            .app
+            .wrap()
+            .query_wasm_smart(
+                token.addr(),
+                &Cw20QueryMsg::Allowance {
+                    owner: instance.c_addr.to_string(),
+                    spender: pool_addr.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(allowance.allowance, Uint128::zero());
+    }
+
+    #[test]
+    fn buy_and_burn_fails_below_min_output() {
+        let mut app = mock_app();
+        let pool_addr = create_swap_pool(&mut app, Denom::Native(IBC_DENOM.to_string()));
+        let funds = [Coin {
+            denom: String::from(IBC_DENOM),
+            amount: Uint128::from(100_000u128),
+        }];
+        let mut instance = mock_instantiate_with_app(app, &funds, vec![]);
+
+        let denom = Denom::Native(IBC_DENOM.to_string());
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr.clone(),
+            denom.clone(),
+            &pool_addr,
+        )
+        .unwrap();
+
+        // swapping 20% of the pool reserves outputs well below its spot price
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom,
+            amount: None,
+            min_output: Uint128::from(90_000u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        let _err = instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::InsufficientSwapOutput {
+                min_output: Uint128::from(90_000u128),
+                output: Uint128::from(83_124u128),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn buy_and_burn_fails_for_non_owner() {
+        let mut app = mock_app();
+        let pool_addr = create_swap_pool(&mut app, Denom::Native(IBC_DENOM.to_string()));
+        let funds = [Coin {
+            denom: String::from(IBC_DENOM),
+            amount: Uint128::from(10_000u128),
+        }];
+        let mut instance = mock_instantiate_with_app(app, &funds, vec![]);
+
+        let denom = Denom::Native(IBC_DENOM.to_string());
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr.clone(),
+            denom.clone(),
+            &pool_addr,
+        )
+        .unwrap();
+
+        // the swap is priced in the same block, so anyone else could sandwich it
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom: denom.clone(),
+            amount: None,
+            min_output: Uint128::from(9_700u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        let _err = instance
+            .app
+            .execute(Addr::unchecked(USER), cosmos_msg)
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        // governance can still buy and burn through sudo
+        instance
+            .app
+            .wasm_sudo(
+                instance.c_addr.clone(),
+                &SudoMsg::BuyAndBurn {
+                    denom,
+                    amount: None,
+                    min_output: Uint128::from(9_700u128),
+                },
+            )
+            .unwrap();
+
+        let info = get_pool_info(&mut instance.app, pool_addr);
+        assert_eq!(info.base_reserve, Uint128::from(POOL_LIQUIDITY - 9775));
+    }
+
+    #[test]
+    fn buy_and_burn_stops_at_total_burn_cap() {
+        let mut app = mock_app();
+        let pool_addr = create_swap_pool(&mut app, Denom::Native(IBC_DENOM.to_string()));
+        let funds = [Coin {
+            denom: String::from(IBC_DENOM),
+            amount: Uint128::from(10_000u128),
+        }];
+        let mut instance = mock_instantiate_with_app(app, &funds, vec![]);
+
+        let denom = Denom::Native(IBC_DENOM.to_string());
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr.clone(),
+            denom.clone(),
+            &pool_addr,
+        )
+        .unwrap();
+
+        // only 5_000 can still be burned, while the whole balance swaps for 9775
+        let total_burn_cap = Uint128::from(5_000u128);
+        instance
+            .app
+            .wasm_sudo(
+                instance.c_addr.clone(),
+                &SudoMsg::SetBurnWindow {
+                    start_time: None,
+                    end_time: None,
+                    total_burn_cap: Some(total_burn_cap),
+                },
+            )
+            .unwrap();
+
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom: denom.clone(),
+            amount: None,
+            min_output: Uint128::from(9_700u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap();
+
+        // the output was reduced to the cap and all of it was burned
+        let info = get_pool_info(&mut instance.app, pool_addr);
+        assert_eq!(
+            info.base_reserve,
+            Uint128::from(POOL_LIQUIDITY) - total_burn_cap
+        );
+
+        let status: BurnStatusResponse = instance
+            .app
+            .wrap()
+            .query_wasm_smart(instance.c_addr.clone(), &QueryMsg::BurnStatus {})
+            .unwrap();
+        assert_eq!(status.total_burned, total_burn_cap);
+
+        let contract_balance = get_balance(&mut instance.app, instance.c_addr.clone());
+        assert_eq!(contract_balance.amount.amount, Uint128::zero());
+
+        // the rest of the ibc balance stays in the contract
+        let ibc_balance = instance
+            .app
+            .wrap()
+            .query_balance(instance.c_addr.clone(), IBC_DENOM)
+            .unwrap();
+        assert_eq!(
+            ibc_balance.amount,
+            Uint128::from(10_000u128 + POOL_LIQUIDITY) - info.quote_reserve
+        );
+
+        // and the cap is now reached
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom,
+            amount: None,
+            min_output: Uint128::from(9_700u128),
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        let _err = instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::TotalBurnCapReached {
+                cap: total_burn_cap
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn sudo_set_swap_pool_fails_for_wrong_denom() {
+        let mut app = mock_app();
+        let pool_addr = create_swap_pool(&mut app, Denom::Native(IBC_DENOM.to_string()));
+        let mut instance = mock_instantiate_with_app(app, &[], vec![]);
+
+        // the pool does not quote this denom
+        execute_sudo_set_swap_pool(
+            &mut instance.app,
+            instance.c_addr,
+            Denom::Native("ibc/other".to_string()),
+            &pool_addr,
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn owner_withdraw_funds_to_address() {
+        let funds = [Coin {
+            denom: String::from(NATIVE_DENOM),
+            amount: Uint128::from(DEFAULT_DAILY_QUOTA),
+        }];
+        let mut instance = mock_instantiate(&funds);
+        let address = String::from("destination_addr");
+
+        // only the owner can withdraw the funds through execute
+        let msg = ExecuteMsg::WithdrawFundsToCommunityPool {
+            address: address.clone(),
+            denom: None,
+            amount: None,
+        };
+        let cosmos_msg = instance.c_template.call(msg).unwrap();
+        instance
+            .app
+            .execute(Addr::unchecked(USER), cosmos_msg.clone())
+            .unwrap_err();
+        instance
+            .app
+            .execute(Addr::unchecked(OWNER), cosmos_msg)
+            .unwrap();
+
+        let balance = instance
+            .app
+            .wrap()
+            .query_balance(address, NATIVE_DENOM)
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::from(DEFAULT_DAILY_QUOTA));
+    }
 }
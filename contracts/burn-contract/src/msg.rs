@@ -1,4 +1,5 @@
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
+use cw20::{Cw20CoinVerified, Cw20ReceiveMsg, Denom};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -7,19 +8,124 @@ pub struct InstantiateMsg {
     pub native_denom: String,
     pub daily_burn_amount: u128,
     pub burn_delay_seconds: u64,
+    pub cw20_tokens: Vec<Cw20BurnInfo>,
+    pub owner: Option<String>,
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub total_burn_cap: Option<Uint128>,
+}
+
+// A cw20 token to be burned on the same schedule as the native_denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20BurnInfo {
+    pub contract_addr: String,
+    pub daily_burn_amount: u128,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     BurnDailyQuota {},
+    Receive(Cw20ReceiveMsg),
+
+    // Swap the contract balance of denom into native_denom through its
+    // configured swap pool and burn the proceeds, owner only.
+    // The swap is refused if it outputs less than min_output
+    BuyAndBurn {
+        denom: Denom,
+        amount: Option<Uint128>,
+        min_output: Uint128,
+    },
+
+    // Config updates available to the owner, mirroring the SudoMsg variants
+    SetMaxDailyBurn {
+        amount: u128,
+    },
+    WithdrawFundsToCommunityPool {
+        address: String,
+        denom: Option<String>,
+        amount: Option<Uint128>,
+    },
+    FundCommunityPool {
+        denom: Option<String>,
+        amount: Option<Uint128>,
+    },
+    SetCw20DailyBurn {
+        contract_addr: String,
+        amount: u128,
+    },
+    RemoveCw20Token {
+        contract_addr: String,
+    },
+    SetSwapPool {
+        denom: Denom,
+        pool_address: String,
+    },
+    RemoveSwapPool {
+        denom: Denom,
+    },
+    SetBurnWindow {
+        start_time: Option<Timestamp>,
+        end_time: Option<Timestamp>,
+        total_burn_cap: Option<Uint128>,
+    },
+    SetPaused {
+        paused: bool,
+    },
+
+    // Two-step ownership transfer, the new owner has to accept the ownership
+    TransferOwnership {
+        new_owner: String,
+    },
+    AcceptOwnership {},
+    RenounceOwnership {},
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum SudoMsg {
-    SetMaxDailyBurn { amount: u128 },
-    WithdrawFundsToCommunityPool { address: String },
+    SetMaxDailyBurn {
+        amount: u128,
+    },
+    WithdrawFundsToCommunityPool {
+        address: String,
+        denom: Option<String>,
+        amount: Option<Uint128>,
+    },
+    FundCommunityPool {
+        denom: Option<String>,
+        amount: Option<Uint128>,
+    },
+    SetCw20DailyBurn {
+        contract_addr: String,
+        amount: u128,
+    },
+    RemoveCw20Token {
+        contract_addr: String,
+    },
+    SetSwapPool {
+        denom: Denom,
+        pool_address: String,
+    },
+    RemoveSwapPool {
+        denom: Denom,
+    },
+    BuyAndBurn {
+        denom: Denom,
+        amount: Option<Uint128>,
+        min_output: Uint128,
+    },
+    SetOwner {
+        owner: Option<String>,
+    },
+    SetBurnWindow {
+        start_time: Option<Timestamp>,
+        end_time: Option<Timestamp>,
+        total_burn_cap: Option<Uint128>,
+    },
+    SetPaused {
+        paused: bool,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -27,6 +133,9 @@ pub enum SudoMsg {
 pub enum QueryMsg {
     Config {},
     Balance {},
+    Cw20Balances {},
+    Ownership {},
+    BurnStatus {},
 }
 
 // A custom struct is defined for each query response
@@ -34,3 +143,22 @@ pub enum QueryMsg {
 pub struct BalanceResponse {
     pub amount: Coin,
 }
+
+// Here the address of each Cw20CoinVerified is the cw20 token contract address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20BalancesResponse {
+    pub balances: Vec<Cw20CoinVerified>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipResponse {
+    pub owner: Option<Addr>,
+    pub pending_owner: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BurnStatusResponse {
+    pub total_burned: Uint128,
+    pub burn_ready_timestamp: Timestamp,
+    pub paused: bool,
+}
@@ -1,7 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Timestamp, Uint128};
+use cosmwasm_std::{Addr, Coin, Timestamp, Uint128};
+use cw20::Denom;
 use cw_storage_plus::Item;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -9,10 +10,42 @@ pub struct Config {
     pub daily_burn_amount: Uint128,
     pub burn_delay_seconds: u64,
     pub native_denom: String,
+    pub cw20_tokens: Vec<Cw20Burn>,
+    pub swap_pools: Vec<SwapPool>,
+    pub owner: Option<Addr>,
+
+    // The burn program only runs between start_time and end_time, while it is not
+    // paused, and until total_burn_cap of the native_denom has been burned
+    pub start_time: Option<Timestamp>,
+    pub end_time: Option<Timestamp>,
+    pub total_burn_cap: Option<Uint128>,
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Cw20Burn {
+    pub contract_addr: Addr,
+    pub daily_burn_amount: Uint128,
+}
+
+// A token-swap pool used by BuyAndBurn to swap denom into the native_denom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapPool {
+    pub denom: Denom,
+    pub pool_addr: Addr,
 }
 
 // This stores the config variables during initialization of the contract
 pub const INIT_CONFIG: Item<Config> = Item::new("INIT_CONFIG");
 
+// This stores the address proposed by the owner until it accepts the ownership
+pub const PENDING_OWNER: Item<Addr> = Item::new("pending_owner");
+
 // This stores the time when the BurnDailyQuota method is ready to be called
 pub const BURN_READY_TIMESTAMP: Item<Timestamp> = Item::new("burn_ready_timestamp");
+
+// This stores the native coins expected from a BuyAndBurn swap until they are burned in the reply
+pub const PENDING_BURN: Item<Coin> = Item::new("pending_burn");
+
+// This keeps track of the total amount of the native_denom burned by the contract
+pub const TOTAL_BURNED: Item<Uint128> = Item::new("total_burned");
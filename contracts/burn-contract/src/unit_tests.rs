@@ -4,12 +4,14 @@ mod tests {
         mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier,
     };
     use cosmwasm_std::{
-        coins, from_binary, Attribute, BankMsg, Coin, CosmosMsg, Empty, Env, MemoryStorage,
-        OwnedDeps, Uint128,
+        coins, from_binary, Addr, Attribute, BankMsg, Binary, Coin, CosmosMsg, Empty, Env,
+        MemoryStorage, OwnedDeps, Uint128,
     };
+    use cw20::{Cw20ReceiveMsg, Denom};
 
     use crate::contract::{execute, instantiate, query, sudo};
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
+    use crate::msg::{BurnStatusResponse, OwnershipResponse};
+    use crate::msg::{Cw20BurnInfo, ExecuteMsg, InstantiateMsg, QueryMsg, SudoMsg};
     use crate::state::Config;
     use crate::ContractError;
 
@@ -34,6 +36,11 @@ mod tests {
             native_denom: String::from(NATIVE_DENOM),
             daily_burn_amount: DEFAULT_DAILY_QUOTA,
             burn_delay_seconds: BURN_DELAY_SECONDS,
+            cw20_tokens: vec![],
+            owner: Some(owner.clone()),
+            start_time: None,
+            end_time: None,
+            total_burn_cap: None,
         };
 
         // we can just call .unwrap() to assert this was a success
@@ -261,6 +268,8 @@ mod tests {
         let address = String::from("destination_addr");
         let msg = SudoMsg::WithdrawFundsToCommunityPool {
             address: address.clone(),
+            denom: None,
+            amount: None,
         };
         let _res = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
 
@@ -295,6 +304,8 @@ mod tests {
         let address = String::from("destination_addr");
         let msg = SudoMsg::WithdrawFundsToCommunityPool {
             address: address.clone(),
+            denom: None,
+            amount: None,
         };
         let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
 
@@ -313,6 +324,8 @@ mod tests {
         let address = String::from("destination_addr");
         let msg = SudoMsg::WithdrawFundsToCommunityPool {
             address: address.clone(),
+            denom: None,
+            amount: None,
         };
         let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
 
@@ -322,4 +335,393 @@ mod tests {
             e => panic!("unexpected error: {}", e),
         }
     }
+
+    #[test]
+    fn instantiate_should_fail_with_invalid_cw20_token() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = InstantiateMsg {
+            native_denom: String::from(NATIVE_DENOM),
+            daily_burn_amount: DEFAULT_DAILY_QUOTA,
+            burn_delay_seconds: BURN_DELAY_SECONDS,
+            cw20_tokens: vec![Cw20BurnInfo {
+                contract_addr: String::from("not_a_token"),
+                daily_burn_amount: DEFAULT_DAILY_QUOTA,
+            }],
+            owner: None,
+            start_time: None,
+            end_time: None,
+            total_burn_cap: None,
+        };
+
+        // the mock querier has no contracts, so the TokenInfo query fails
+        let info = mock_info("creator", &[]);
+        let _err = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InvalidCw20Token { address } => assert_eq!(address, "not_a_token"),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn receive_should_fail_for_unsupported_cw20_token() {
+        let mut instance = proper_initialization(&[]);
+
+        // a token that is not in config.cw20_tokens tries to send funds to the contract
+        let info = mock_info("unknown_token", &[]);
+        let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: instance.owner.clone(),
+            amount: Uint128::from(100u128),
+            msg: Default::default(),
+        });
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+
+        // we expect the UnsupportedCw20Token
+        match _err {
+            ContractError::UnsupportedCw20Token { address } => {
+                assert_eq!(address, "unknown_token")
+            }
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn sudo_remove_cw20_token_should_fail_for_unknown_token() {
+        let mut instance = proper_initialization(&[]);
+
+        let msg = SudoMsg::RemoveCw20Token {
+            contract_addr: String::from("unknown_token"),
+        };
+        let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
+
+        // we expect the UnsupportedCw20Token
+        match _err {
+            ContractError::UnsupportedCw20Token { address: _ } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn buy_and_burn_should_fail_without_swap_pool() {
+        let funds = coins(DEFAULT_DAILY_QUOTA, "ibc/denom");
+        let mut instance = proper_initialization(&funds);
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BuyAndBurn {
+            denom: Denom::Native(String::from("ibc/denom")),
+            amount: None,
+            min_output: Uint128::zero(),
+        };
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+
+        // we expect the NoSwapPoolForDenom
+        match _err {
+            ContractError::NoSwapPoolForDenom { denom } => assert_eq!(denom, "ibc/denom"),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn execute_set_max_daily_burn_by_owner() {
+        let mut instance = proper_initialization(&[]);
+
+        // only the owner can update the config through execute
+        let amount = 100u128;
+        let info = mock_info("not_owner", &[]);
+        let msg = ExecuteMsg::SetMaxDailyBurn { amount };
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::SetMaxDailyBurn { amount };
+        execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap();
+
+        // query and verify state
+        let res = query(instance.deps.as_ref(), instance.env, QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(Uint128::from(amount), config.daily_burn_amount);
+    }
+
+    #[test]
+    fn execute_two_step_ownership_transfer() {
+        let mut instance = proper_initialization(&[]);
+        let new_owner = String::from("new_owner");
+
+        // accepting fails when there is no pending transfer
+        let info = mock_info(&new_owner, &[]);
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::NoPendingOwner {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the owner proposes the new owner
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::TransferOwnership {
+            new_owner: new_owner.clone(),
+        };
+        execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap();
+
+        // only the proposed owner can accept
+        let info = mock_info("not_owner", &[]);
+        let msg = ExecuteMsg::AcceptOwnership {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info(&new_owner, &[]);
+        let msg = ExecuteMsg::AcceptOwnership {};
+        execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap();
+
+        let res = query(
+            instance.deps.as_ref(),
+            instance.env.clone(),
+            QueryMsg::Ownership {},
+        )
+        .unwrap();
+        let ownership: OwnershipResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            ownership,
+            OwnershipResponse {
+                owner: Some(Addr::unchecked(new_owner)),
+                pending_owner: None,
+            }
+        );
+
+        // the previous owner is no longer authorized
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::SetMaxDailyBurn { amount: 100u128 };
+        let _err = execute(instance.deps.as_mut(), instance.env, info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn sudo_set_owner_removes_owner() {
+        let mut instance = proper_initialization(&[]);
+
+        // governance removes the owner
+        let msg = SudoMsg::SetOwner { owner: None };
+        sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::SetMaxDailyBurn { amount: 100u128 };
+        let _err = execute(instance.deps.as_mut(), instance.env, info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn sudo_withdraw_funds_to_address_should_allow_partial_withdrawals() {
+        let mut funds = coins(DEFAULT_DAILY_QUOTA, NATIVE_DENOM);
+        funds.push(Coin {
+            denom: String::from("ibc/denom"),
+            amount: Uint128::from(1000u128),
+        });
+        let mut instance = proper_initialization(&funds);
+
+        // we withdraw part of the ibc/denom balance
+        let address = String::from("destination_addr");
+        let msg = SudoMsg::WithdrawFundsToCommunityPool {
+            address: address.clone(),
+            denom: Some(String::from("ibc/denom")),
+            amount: Some(Uint128::from(400u128)),
+        };
+        let _res = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: address.clone(),
+                amount: coins(400u128, "ibc/denom"),
+            })
+        );
+
+        // withdrawing more than the balance fails
+        let msg = SudoMsg::WithdrawFundsToCommunityPool {
+            address,
+            denom: Some(String::from("ibc/denom")),
+            amount: Some(Uint128::from(1001u128)),
+        };
+        let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
+        match _err {
+            ContractError::InsufficientContractBalance {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn sudo_fund_community_pool_should_emit_msg_fund_community_pool() {
+        let funds = coins(DEFAULT_DAILY_QUOTA, NATIVE_DENOM);
+        let mut instance = proper_initialization(&funds);
+
+        let msg = SudoMsg::FundCommunityPool {
+            denom: None,
+            amount: Some(Uint128::from(100u128)),
+        };
+        let _res = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        // MsgFundCommunityPool { amount: [Coin { denom, amount }], depositor }
+        let depositor = instance.env.contract.address.as_bytes();
+        let mut value = vec![0x0a, 13, 0x0a, 6];
+        value.extend_from_slice(NATIVE_DENOM.as_bytes());
+        value.extend_from_slice(&[0x12, 3]);
+        value.extend_from_slice(b"100");
+        value.extend_from_slice(&[0x12, depositor.len() as u8]);
+        value.extend_from_slice(depositor);
+
+        assert_eq!(_res.messages.len(), 1);
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Stargate {
+                type_url: String::from("/cosmos.distribution.v1beta1.MsgFundCommunityPool"),
+                value: Binary::from(value),
+            }
+        );
+    }
+
+    #[test]
+    fn sudo_fund_community_pool_should_fail_with_no_balance() {
+        let mut instance = proper_initialization(&[]);
+
+        let msg = SudoMsg::FundCommunityPool {
+            denom: None,
+            amount: None,
+        };
+        let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
+        match _err {
+            ContractError::InsufficientContractBalance {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn burn_daily_quota_should_fail_when_paused() {
+        let funds = coins(DEFAULT_DAILY_QUOTA, NATIVE_DENOM);
+        let mut instance = proper_initialization(&funds);
+
+        let msg = SudoMsg::SetPaused { paused: true };
+        sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::BurnPaused {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // once unpaused, the burn goes through
+        let msg = SudoMsg::SetPaused { paused: false };
+        sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn burn_daily_quota_should_only_run_within_burn_window() {
+        let funds = coins(DEFAULT_DAILY_QUOTA, NATIVE_DENOM);
+        let mut instance = proper_initialization(&funds);
+
+        // the burn window opens in one hour and closes a day after that
+        let start_time = instance.env.block.time.plus_seconds(3600);
+        let end_time = start_time.plus_seconds(BURN_DELAY_SECONDS);
+        let msg = SudoMsg::SetBurnWindow {
+            start_time: Some(start_time),
+            end_time: Some(end_time),
+            total_burn_cap: None,
+        };
+        sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::BurnNotStarted { start_time: t } => assert_eq!(t, start_time),
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // after the end_time the burn is refused
+        instance.env.block.time = end_time;
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::BurnEnded { end_time: t } => assert_eq!(t, end_time),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn burn_daily_quota_should_stop_at_total_burn_cap() {
+        let funds = coins(DEFAULT_DAILY_QUOTA * 2, NATIVE_DENOM);
+        let mut instance = proper_initialization(&funds);
+
+        // only half of the daily quota is left to be burned
+        let cap = Uint128::from(DEFAULT_DAILY_QUOTA / 2);
+        let msg = SudoMsg::SetBurnWindow {
+            start_time: None,
+            end_time: None,
+            total_burn_cap: Some(cap),
+        };
+        sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let _res = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    denom: String::from(NATIVE_DENOM),
+                    amount: cap,
+                }]
+            })
+        );
+
+        let res = query(
+            instance.deps.as_ref(),
+            instance.env.clone(),
+            QueryMsg::BurnStatus {},
+        )
+        .unwrap();
+        let status: BurnStatusResponse = from_binary(&res).unwrap();
+        assert_eq!(status.total_burned, cap);
+
+        // the next burn is refused because the cap has been reached
+        instance.env.block.time = instance.env.block.time.plus_seconds(BURN_DELAY_SECONDS);
+        let info = mock_info(&instance.owner, &[]);
+        let msg = ExecuteMsg::BurnDailyQuota {};
+        let _err = execute(instance.deps.as_mut(), instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::TotalBurnCapReached { cap: c } => assert_eq!(c, cap),
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn sudo_set_burn_window_should_fail_with_invalid_window() {
+        let mut instance = proper_initialization(&[]);
+
+        let msg = SudoMsg::SetBurnWindow {
+            start_time: Some(instance.env.block.time),
+            end_time: Some(instance.env.block.time),
+            total_burn_cap: None,
+        };
+        let _err = sudo(instance.deps.as_mut(), instance.env.clone(), msg).unwrap_err();
+        match _err {
+            ContractError::InvalidBurnWindow {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
 }
@@ -1,20 +1,34 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    attr, entry_point, to_binary, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut,
-    Env, MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+    attr, to_binary, to_vec, Addr, BankMsg, Binary, BlockInfo, Coin, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Reply, ReplyOn, Response, StdError, StdResult, SubMsg, Timestamp, Uint128,
+    WasmMsg,
 };
 use cw0::parse_reply_instantiate_data;
 use cw20::{Cw20ExecuteMsg, Denom, Expiration, MinterResponse};
+use proto_encoding::{decode_varint, encode_bytes_field, encode_varint_field};
+use serde::Serialize;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InfoResponse, InstantiateMsg, QueryMsg, TokenSelect};
+use crate::msg::{
+    ExecuteMsg, IbcLifecycleComplete, IbcSwapDestination, InfoResponse, InstantiateMsg, QueryMsg,
+    SudoMsg, TokenSelect,
+};
 use crate::state::{
-    SwapPrice, Token, TokenAmount, BASE_TOKEN, LP_TOKEN, NATIVE_DENOM, QUOTE_TOKEN,
+    PendingForward, SwapPrice, Token, TokenAmount, BASE_TOKEN, FORWARD_IN_FLIGHT, LP_TOKEN,
+    NATIVE_DENOM, PENDING_FORWARDS, QUOTE_TOKEN,
 };
 
 // Version info for migration info
 pub const CONTRACT_NAME: &str = "huahuaswap";
 pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 const INSTANTIATE_LP_TOKEN_REPLY_ID: u64 = 0;
+const IBC_FORWARD_REPLY_ID: u64 = 1;
+
+// The Stargate type url of the ICS-20 transfer, sent instead of IbcMsg::Transfer
+// which has no memo to request the IBC hooks callback
+pub const IBC_TRANSFER_TYPE_URL: &str = "/ibc.applications.transfer.v1.MsgTransfer";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -109,6 +123,7 @@ pub fn instantiate(
 pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
     match msg.id {
         INSTANTIATE_LP_TOKEN_REPLY_ID => handle_instantiate_reply(deps, msg),
+        IBC_FORWARD_REPLY_ID => handle_ibc_forward_reply(deps, msg),
         id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
     }
 }
@@ -131,6 +146,34 @@ fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
     Ok(Response::new().add_attribute("token_contract_addr", data.contract_address))
 }
 
+/**
+ * Handle reply for an IbcSwap forward
+ * Record the forward under the sequence of its packet until the IBC hooks
+ * callback reports its ack or timeout
+ */
+fn handle_ibc_forward_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    // MsgTransferResponse { uint64 sequence = 1; }
+    let data = msg
+        .result
+        .into_result()
+        .map_err(StdError::generic_err)?
+        .data
+        .unwrap_or_default();
+    let sequence = match decode_varint(&data) {
+        Some((key, len)) if key == 1 << 3 => decode_varint(&data[len..]).map(|(value, _)| value),
+        _ => None,
+    }
+    .ok_or_else(|| StdError::generic_err("Error parsing MsgTransferResponse"))?;
+
+    let (channel_id, forward) = FORWARD_IN_FLIGHT.load(deps.storage)?;
+    FORWARD_IN_FLIGHT.remove(deps.storage);
+    PENDING_FORWARDS.save(deps.storage, (&channel_id, sequence), &forward)?;
+
+    Ok(Response::new()
+        .add_attribute("channel_id", channel_id)
+        .add_attribute("sequence", sequence.to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -210,6 +253,20 @@ pub fn execute(
             min_quote_output_amount,
             expiration,
         ),
+        ExecuteMsg::IbcSwap {
+            min_output_amount,
+            destination,
+            refund_address,
+            expiration,
+        } => execute_ibc_swap(
+            deps,
+            env,
+            info,
+            min_output_amount,
+            destination,
+            refund_address,
+            expiration,
+        ),
     }
 }
 
@@ -358,6 +415,55 @@ fn get_bank_transfer_to_msg(recipient: &Addr, denom: &str, native_amount: Uint12
     transfer_bank_cosmos_msg
 }
 
+#[derive(Serialize)]
+struct IbcCallbackMemo {
+    ibc_callback: String,
+}
+
+/**
+ * Build a MsgTransfer stargate message whose memo asks the IBC hooks module
+ * to call the sudo entry point of this contract with the ack or timeout
+ */
+fn get_ibc_transfer_msg(
+    env: &Env,
+    channel_id: &str,
+    receiver: &str,
+    amount: &Coin,
+    timeout: Timestamp,
+) -> StdResult<CosmosMsg> {
+    let memo = to_vec(&IbcCallbackMemo {
+        ibc_callback: env.contract.address.to_string(),
+    })?;
+
+    // message MsgTransfer {
+    //   string source_port = 1;
+    //   string source_channel = 2;
+    //   cosmos.base.v1beta1.Coin token = 3;
+    //   string sender = 4;
+    //   string receiver = 5;
+    //   ibc.core.client.v1.Height timeout_height = 6;
+    //   uint64 timeout_timestamp = 7;
+    //   string memo = 8;
+    // }
+    let mut token = vec![];
+    encode_bytes_field(&mut token, 1, amount.denom.as_bytes());
+    encode_bytes_field(&mut token, 2, amount.amount.to_string().as_bytes());
+
+    let mut value = vec![];
+    encode_bytes_field(&mut value, 1, b"transfer");
+    encode_bytes_field(&mut value, 2, channel_id.as_bytes());
+    encode_bytes_field(&mut value, 3, &token);
+    encode_bytes_field(&mut value, 4, env.contract.address.as_bytes());
+    encode_bytes_field(&mut value, 5, receiver.as_bytes());
+    encode_varint_field(&mut value, 7, timeout.nanos());
+    encode_bytes_field(&mut value, 8, &memo);
+
+    Ok(CosmosMsg::Stargate {
+        type_url: IBC_TRANSFER_TYPE_URL.to_string(),
+        value: Binary(value),
+    })
+}
+
 fn get_cw20_transfer_to_msg(
     recipient: &Addr,
     token_addr: &Addr,
@@ -617,6 +723,7 @@ pub fn execute_remove_liquidity(
  *
  * What this means is that the swap_fee is always charged to the quote token.
  */
+#[allow(clippy::too_many_arguments)]
 pub fn execute_swap(
     _env: Env,
     deps: DepsMut,
@@ -1006,6 +1113,232 @@ pub fn execute_pass_through_swap(
     ]))
 }
 
+/**
+ * Swap entry for IBC hooks, where the memo of an ICS-20 transfer to this contract
+ * is executed as {"wasm":{"contract":"<pool>","msg":{"ibc_swap":{..}}}}.
+ *
+ * The funds are the tokens received by the packet, and info.sender is the
+ * intermediary address the hooks module derives from the channel and the original sender,
+ * so nothing is ever sent back to it.
+ *
+ * The received amount is swapped as an exact input, and the output is either sent to a
+ * local recipient or forwarded over ICS-20 with a timeout.
+ *
+ * If the swap itself fails, the error fails the packet and the source chain refunds
+ * the packet sender. A forward is tracked by its packet sequence and sent to the
+ * mandatory refund_address if it is acknowledged with an error or times out.
+ */
+pub fn execute_ibc_swap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    min_output_amount: Uint128,
+    destination: IbcSwapDestination,
+    refund_address: Option<Addr>,
+    expiration: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    // here we load the token reserves
+    let base = BASE_TOKEN.load(deps.storage)?;
+    let quote = QUOTE_TOKEN.load(deps.storage)?;
+
+    // The packet must carry exactly one native token of the pool
+    let input = match info.funds.as_slice() {
+        [coin] => coin.clone(),
+        _ => return Err(ContractError::InvalidIbcSwapFunds {}),
+    };
+    let input_denom = Denom::Native(input.denom.clone());
+    let input_token = if input_denom == base.denom {
+        TokenSelect::Base
+    } else if input_denom == quote.denom {
+        TokenSelect::Quote
+    } else {
+        return Err(ContractError::InvalidIbcSwapFunds {});
+    };
+
+    // Validate the destination and refund address before swapping
+    let refund_address = match &destination {
+        IbcSwapDestination::Local { recipient } => {
+            deps.api.addr_validate(recipient.as_str())?;
+            None
+        }
+        IbcSwapDestination::Ibc {
+            channel_id,
+            receiver,
+            timeout_seconds,
+        } => {
+            if channel_id.is_empty() || receiver.is_empty() || *timeout_seconds == 0 {
+                return Err(ContractError::InvalidIbcDestination {});
+            }
+
+            let output_denom = match input_token {
+                TokenSelect::Base => &quote.denom,
+                TokenSelect::Quote => &base.denom,
+            };
+            if let Denom::Cw20(_) = output_denom {
+                return Err(ContractError::IbcForwardNotNative {});
+            }
+            let refund_address = refund_address.ok_or(ContractError::MissingRefundAddress {})?;
+            Some(deps.api.addr_validate(refund_address.as_str())?)
+        }
+    };
+
+    check_expiration(&expiration, &env.block)?;
+
+    // Get the swap price for the exact input amount in either direction
+    let swap_price = match input_token {
+        TokenSelect::Base => exact_input_variable_output(
+            input.amount,
+            min_output_amount,
+            base.reserve,
+            quote.reserve,
+            base.denom.clone(),
+            quote.denom.clone(),
+        ),
+        TokenSelect::Quote => exact_input_variable_output(
+            input.amount,
+            min_output_amount,
+            quote.reserve,
+            base.reserve,
+            quote.denom.clone(),
+            base.denom.clone(),
+        ),
+    }?;
+
+    // Update the reserves
+    match input_token {
+        TokenSelect::Base => {
+            BASE_TOKEN.update(deps.storage, |mut base| -> Result<_, ContractError> {
+                base.reserve += swap_price.input.amount;
+                Ok(base)
+            })?;
+            QUOTE_TOKEN.update(deps.storage, |mut quote| -> Result<_, ContractError> {
+                quote.reserve -= swap_price.output.amount;
+                Ok(quote)
+            })?;
+        }
+        TokenSelect::Quote => {
+            QUOTE_TOKEN.update(deps.storage, |mut quote| -> Result<_, ContractError> {
+                quote.reserve += swap_price.input.amount;
+                Ok(quote)
+            })?;
+            BASE_TOKEN.update(deps.storage, |mut base| -> Result<_, ContractError> {
+                base.reserve -= swap_price.output.amount;
+                Ok(base)
+            })?;
+        }
+    }
+
+    // Deliver the output token
+    let output_msg = match (destination, swap_price.output.denom.clone(), refund_address) {
+        (IbcSwapDestination::Local { recipient }, Denom::Native(denom), _) => SubMsg::new(
+            get_bank_transfer_to_msg(&recipient, &denom, swap_price.output.amount),
+        ),
+        (IbcSwapDestination::Local { recipient }, Denom::Cw20(addr), _) => SubMsg::new(
+            get_cw20_transfer_to_msg(&recipient, &addr, swap_price.output.amount)?,
+        ),
+        (
+            IbcSwapDestination::Ibc {
+                channel_id,
+                receiver,
+                timeout_seconds,
+            },
+            Denom::Native(denom),
+            Some(refund_address),
+        ) => {
+            let amount = Coin {
+                denom,
+                amount: swap_price.output.amount,
+            };
+            let msg = get_ibc_transfer_msg(
+                &env,
+                &channel_id,
+                &receiver,
+                &amount,
+                env.block.time.plus_seconds(timeout_seconds),
+            )?;
+
+            // The reply records the forward under its packet sequence
+            FORWARD_IN_FLIGHT.save(
+                deps.storage,
+                &(
+                    channel_id,
+                    PendingForward {
+                        refund_address,
+                        amount,
+                    },
+                ),
+            )?;
+            SubMsg::reply_on_success(msg, IBC_FORWARD_REPLY_ID)
+        }
+        // unreachable because both were rejected above
+        (IbcSwapDestination::Ibc { .. }, Denom::Cw20(_), _) => {
+            return Err(ContractError::IbcForwardNotNative {});
+        }
+        (IbcSwapDestination::Ibc { .. }, Denom::Native(_), None) => {
+            return Err(ContractError::MissingRefundAddress {});
+        }
+    };
+
+    Ok(Response::new()
+        .add_submessage(output_msg)
+        .add_attributes(vec![
+            attr("method", "ibc_swap"),
+            attr("sender", info.sender),
+            attr("input_amount", swap_price.input.amount),
+            attr("input_denom", format!("{:?}", swap_price.input.denom)),
+            attr("output_amount", swap_price.output.amount),
+            attr("output_denom", format!("{:?}", swap_price.output.denom)),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+            channel,
+            sequence,
+            success,
+            ..
+        }) => sudo_ibc_forward_complete(deps, channel, sequence, success),
+        SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout { channel, sequence }) => {
+            sudo_ibc_forward_complete(deps, channel, sequence, false)
+        }
+    }
+}
+
+/**
+ * Settle an IbcSwap forward once its packet is acknowledged or times out,
+ * the ICS-20 module returns the tokens of a failed forward to this contract
+ * and they are sent to the refund_address of the forward
+ */
+fn sudo_ibc_forward_complete(
+    deps: DepsMut,
+    channel: String,
+    sequence: u64,
+    success: bool,
+) -> Result<Response, ContractError> {
+    let forward = PENDING_FORWARDS.load(deps.storage, (&channel, sequence))?;
+    PENDING_FORWARDS.remove(deps.storage, (&channel, sequence));
+
+    let mut res = Response::new().add_attributes(vec![
+        attr("method", "ibc_lifecycle_complete"),
+        attr("channel", channel),
+        attr("sequence", sequence.to_string()),
+        attr("success", success.to_string()),
+    ]);
+    if !success {
+        res = res
+            .add_message(get_bank_transfer_to_msg(
+                &forward.refund_address,
+                &forward.amount.denom,
+                forward.amount.amount,
+            ))
+            .add_attribute("refund_address", forward.refund_address);
+    }
+
+    Ok(res)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
@@ -1024,6 +1357,6 @@ pub fn query_info(deps: Deps) -> StdResult<InfoResponse> {
         quote_reserve: quote.reserve,
         quote_denom: quote.denom,
         lp_token_supply: get_lp_token_supply(deps, &lp_token_address)?,
-        lp_token_address: lp_token_address,
+        lp_token_address,
     })
 }
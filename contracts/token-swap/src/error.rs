@@ -46,7 +46,10 @@ pub enum ContractError {
     MsgExpirationError {},
 
     #[error("IncorrectAmountProvided")]
-    IncorrectAmountProvided { provided: Uint128, required: Uint128},
+    IncorrectAmountProvided {
+        provided: Uint128,
+        required: Uint128,
+    },
 
     #[error("Non zero amount for base and quote tokens is expected")]
     NonZeroInputAmountExpected {},
@@ -60,6 +63,18 @@ pub enum ContractError {
     #[error("Quote denom is not a cw20 token")]
     InvalidQuoteDenom {},
 
+    #[error("IBC swap expects a single native token of the pool")]
+    InvalidIbcSwapFunds {},
+
+    #[error("Invalid IBC swap destination")]
+    InvalidIbcDestination {},
+
+    #[error("Only native tokens can be forwarded over IBC")]
+    IbcForwardNotNative {},
+
+    #[error("An IBC forward requires a refund address")]
+    MissingRefundAddress {},
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
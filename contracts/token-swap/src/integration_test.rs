@@ -1,6 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::msg::{ExecuteMsg, InfoResponse, InstantiateMsg, QueryMsg, TokenSelect};
+    use crate::msg::{
+        ExecuteMsg, IbcSwapDestination, InfoResponse, InstantiateMsg, QueryMsg, TokenSelect,
+    };
     use cosmwasm_std::{Addr, Coin, Empty, Uint128};
     use cw20::{Cw20Coin, Cw20Contract, Cw20ExecuteMsg, Denom, Expiration};
     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
@@ -1974,4 +1976,100 @@ mod tests {
             Uint128::new(SUPPLY) - liquidity_added + min_quote_output_amount
         );
     }
+
+    #[test]
+    fn test_ibc_swap_to_local_recipient() {
+        // Step 1
+        // Setup the mock app, USER stands in for the IBC hooks intermediary sender
+        // ------------------------------------------------------------------------------
+
+        let mut router = mock_app();
+        let owner = Addr::unchecked(USER);
+        let recipient = Addr::unchecked("recipient");
+
+        // amm contract instance
+        let amm_addr = _instantiate_amm_with_native_as_quote(&mut router, IBC_DENOM_1.into());
+
+        // Step 2
+        // Add liquidity to the amm
+        // ------------------------------------------------------------------------------
+        let liquidity_added = Uint128::new(100_000);
+        router
+            .execute_contract(
+                owner.clone(),
+                amm_addr.clone(),
+                &ExecuteMsg::AddLiquidity {
+                    base_token_amount: liquidity_added,
+                    max_quote_token_amount: liquidity_added,
+                    expiration: None,
+                },
+                &[
+                    Coin {
+                        denom: NATIVE_DENOM.into(),
+                        amount: liquidity_added,
+                    },
+                    Coin {
+                        denom: IBC_DENOM_1.into(),
+                        amount: liquidity_added,
+                    },
+                ],
+            )
+            .unwrap();
+
+        // Step 3
+        // A failed IbcSwap fails the packet, the received IBC tokens go back to the
+        // packet sender and the reserves are untouched
+        // ------------------------------------------------------------------------------
+        let received = Coin {
+            denom: IBC_DENOM_1.into(),
+            amount: Uint128::new(10_000),
+        };
+        let sender_balance = bank_balance(&mut router, &owner, IBC_DENOM_1.to_string());
+        router
+            .execute_contract(
+                owner.clone(),
+                amm_addr.clone(),
+                &ExecuteMsg::IbcSwap {
+                    min_output_amount: Uint128::new(9064),
+                    destination: IbcSwapDestination::Local {
+                        recipient: recipient.clone(),
+                    },
+                    refund_address: None,
+                    expiration: None,
+                },
+                std::slice::from_ref(&received),
+            )
+            .unwrap_err();
+
+        let balance = bank_balance(&mut router, &owner, IBC_DENOM_1.to_string());
+        assert_eq!(balance, sender_balance);
+        let info = get_amm_contract_info(&mut router, &amm_addr);
+        assert_eq!(info.quote_reserve, Uint128::new(100_000));
+
+        // Step 4
+        // Swap the received IBC tokens and send the native output to recipient
+        // ------------------------------------------------------------------------------
+        router
+            .execute_contract(
+                owner,
+                amm_addr.clone(),
+                &ExecuteMsg::IbcSwap {
+                    min_output_amount: Uint128::new(9063),
+                    destination: IbcSwapDestination::Local {
+                        recipient: recipient.clone(),
+                    },
+                    refund_address: None,
+                    expiration: None,
+                },
+                &[received],
+            )
+            .unwrap();
+
+        let balance = bank_balance(&mut router, &recipient, NATIVE_DENOM.to_string());
+        assert_eq!(balance.amount, Uint128::new(9063));
+
+        let info = get_amm_contract_info(&mut router, &amm_addr);
+        assert_eq!(info.base_reserve, Uint128::new(100_000 - 9063));
+        assert_eq!(info.quote_reserve, Uint128::new(110_000));
+    }
 }
@@ -24,6 +24,23 @@ pub enum TokenSelect {
     Quote,
 }
 
+// Where the output of an IbcSwap is delivered
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcSwapDestination {
+    // Send the output to an address on this chain
+    Local {
+        recipient: Addr,
+    },
+
+    // Forward the output over ICS-20 to a receiver on the other end of channel_id
+    Ibc {
+        channel_id: String,
+        receiver: String,
+        timeout_seconds: u64,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
@@ -62,6 +79,41 @@ pub enum ExecuteMsg {
         min_quote_output_amount: Uint128,
         expiration: Option<Expiration>,
     },
+
+    // Swap entry called from an IBC hooks memo, swaps the exact amount of the
+    // single native token received and delivers the output to destination.
+    // A failed swap fails the ICS-20 packet so it is refunded to its sender on the
+    // source chain. refund_address is required for IBC destinations and receives
+    // the output if the forward is acknowledged with an error or times out
+    IbcSwap {
+        min_output_amount: Uint128,
+        destination: IbcSwapDestination,
+        refund_address: Option<Addr>,
+        expiration: Option<Expiration>,
+    },
+}
+
+// Called by the IBC hooks module once an ICS-20 transfer sent with an
+// ibc_callback memo is acknowledged or times out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    IbcLifecycleComplete(IbcLifecycleComplete),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IbcLifecycleComplete {
+    IbcAck {
+        channel: String,
+        sequence: u64,
+        ack: String,
+        success: bool,
+    },
+    IbcTimeout {
+        channel: String,
+        sequence: u64,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -1,6 +1,6 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Coin, Uint128};
 use cw20::Denom;
-use cw_storage_plus::Item;
+use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -22,7 +22,21 @@ pub struct SwapPrice {
     pub output: TokenAmount,
 }
 
+// The output of an IbcSwap forwarded over ICS-20, sent to refund_address
+// if the transfer is acknowledged with an error or times out
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingForward {
+    pub refund_address: Addr,
+    pub amount: Coin,
+}
+
 pub const LP_TOKEN: Item<Addr> = Item::new("lp_token");
 pub const NATIVE_DENOM: Item<Denom> = Item::new("native_denom");
 pub const BASE_TOKEN: Item<Token> = Item::new("base_token");
 pub const QUOTE_TOKEN: Item<Token> = Item::new("quote_token");
+
+// The forward being sent, until the reply returns its packet sequence
+pub const FORWARD_IN_FLIGHT: Item<(String, PendingForward)> = Item::new("forward_in_flight");
+
+// The forwards waiting for their ack or timeout, by source channel and sequence
+pub const PENDING_FORWARDS: Map<(&str, u64), PendingForward> = Map::new("pending_forwards");
@@ -2,17 +2,24 @@
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
     use cosmwasm_std::{
-        to_binary, Addr, Attribute, Empty, MemoryStorage, OwnedDeps, Reply, ReplyOn, SubMsg,
-        SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
+        coins, to_binary, Addr, Attribute, BankMsg, Binary, CosmosMsg, Empty, MemoryStorage,
+        OwnedDeps, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult, Uint128, WasmMsg,
     };
     use cw20::{Denom, MinterResponse};
+    use proto_encoding::{encode_bytes_field, encode_varint_field};
 
+    use crate::contract::execute;
     use crate::contract::{
         exact_input_variable_output, exact_output_variable_input, get_lp_token_amount_to_mint,
-        get_required_quote_token_amount, instantiate, reply,
+        get_required_quote_token_amount, instantiate, reply, sudo, IBC_TRANSFER_TYPE_URL,
+    };
+    use crate::msg::{
+        ExecuteMsg, IbcLifecycleComplete, IbcSwapDestination, InstantiateMsg, SudoMsg,
+    };
+    use crate::state::{
+        PendingForward, SwapPrice, Token, TokenAmount, BASE_TOKEN, LP_TOKEN, PENDING_FORWARDS,
+        QUOTE_TOKEN,
     };
-    use crate::msg::InstantiateMsg;
-    use crate::state::{SwapPrice, TokenAmount, LP_TOKEN};
     use crate::ContractError;
 
     struct InstantiationResponse {
@@ -309,4 +316,273 @@ mod tests {
             }
         );
     }
+
+    // Seeds the pool reserves without going through AddLiquidity
+    fn seed_reserves(deps: &mut InstantiationResponse, quote_denom: Denom, reserve: Uint128) {
+        BASE_TOKEN
+            .save(
+                &mut deps.deps.storage,
+                &Token {
+                    reserve,
+                    denom: Denom::Native(String::from("native")),
+                },
+            )
+            .unwrap();
+        QUOTE_TOKEN
+            .save(
+                &mut deps.deps.storage,
+                &Token {
+                    reserve,
+                    denom: quote_denom,
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_ibc_swap() {
+        let mut res = proper_initialization();
+        seed_reserves(
+            &mut res,
+            Denom::Native(String::from("ibc/token")),
+            Uint128::new(100_000),
+        );
+        let env = mock_env();
+        let forward = IbcSwapDestination::Ibc {
+            channel_id: String::from("channel-0"),
+            receiver: String::from("osmo1receiver"),
+            timeout_seconds: 600,
+        };
+
+        // Expect an error when the funds are not a single pool token
+        let info = mock_info("ibc_hooks_sender", &coins(10_000, "ibc/other"));
+        let _err = execute(
+            res.deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::zero(),
+                destination: forward.clone(),
+                refund_address: None,
+                expiration: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidIbcSwapFunds {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // Expect an error when the forward has no timeout
+        let info = mock_info("ibc_hooks_sender", &coins(10_000, "ibc/token"));
+        let _err = execute(
+            res.deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::zero(),
+                destination: IbcSwapDestination::Ibc {
+                    channel_id: String::from("channel-0"),
+                    receiver: String::from("osmo1receiver"),
+                    timeout_seconds: 0,
+                },
+                refund_address: None,
+                expiration: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidIbcDestination {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // Expect an error when the forward has no refund address
+        let _err = execute(
+            res.deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::new(9063),
+                destination: forward.clone(),
+                refund_address: None,
+                expiration: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::MissingRefundAddress {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // A failed swap fails the packet even with a refund address, so the
+        // source chain refunds the packet sender
+        let _err = execute(
+            res.deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::new(9064),
+                destination: forward.clone(),
+                refund_address: Some(Addr::unchecked("refund")),
+                expiration: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::SwapMinError { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // q = 9063 native is forwarded over IBC with the timeout and the hooks callback
+        let _res = execute(
+            res.deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::new(9063),
+                destination: forward.clone(),
+                refund_address: Some(Addr::unchecked("refund")),
+                expiration: None,
+            },
+        )
+        .unwrap();
+        let mut token = vec![];
+        encode_bytes_field(&mut token, 1, b"native");
+        encode_bytes_field(&mut token, 2, b"9063");
+        let mut value = vec![];
+        encode_bytes_field(&mut value, 1, b"transfer");
+        encode_bytes_field(&mut value, 2, b"channel-0");
+        encode_bytes_field(&mut value, 3, &token);
+        encode_bytes_field(&mut value, 4, env.contract.address.as_bytes());
+        encode_bytes_field(&mut value, 5, b"osmo1receiver");
+        encode_varint_field(&mut value, 7, env.block.time.plus_seconds(600).nanos());
+        encode_bytes_field(
+            &mut value,
+            8,
+            format!("{{\"ibc_callback\":\"{}\"}}", env.contract.address).as_bytes(),
+        );
+        assert_eq!(
+            _res.messages,
+            vec![SubMsg::reply_on_success(
+                CosmosMsg::Stargate {
+                    type_url: IBC_TRANSFER_TYPE_URL.to_string(),
+                    value: Binary(value),
+                },
+                1,
+            )]
+        );
+        let base = BASE_TOKEN.load(&res.deps.storage).unwrap();
+        let quote = QUOTE_TOKEN.load(&res.deps.storage).unwrap();
+        assert_eq!(base.reserve, Uint128::new(100_000 - 9063));
+        assert_eq!(quote.reserve, Uint128::new(110_000));
+
+        // The reply records the forward under the sequence of MsgTransferResponse
+        let transfer_reply = |sequence: u8| Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![8, sequence].into()),
+            }),
+        };
+        reply(res.deps.as_mut(), env.clone(), transfer_reply(7)).unwrap();
+        assert_eq!(
+            PENDING_FORWARDS
+                .load(&res.deps.storage, ("channel-0", 7))
+                .unwrap(),
+            PendingForward {
+                refund_address: Addr::unchecked("refund"),
+                amount: coins(9063, "native")[0].clone(),
+            }
+        );
+
+        // A successful ack settles the forward
+        let ack = |sequence: u64, success: bool| {
+            SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcAck {
+                channel: String::from("channel-0"),
+                sequence,
+                ack: String::from("AQ=="),
+                success,
+            })
+        };
+        let _res = sudo(res.deps.as_mut(), env.clone(), ack(7, true)).unwrap();
+        assert!(_res.messages.is_empty());
+        assert!(PENDING_FORWARDS
+            .may_load(&res.deps.storage, ("channel-0", 7))
+            .unwrap()
+            .is_none());
+
+        // Unknown or settled forwards are refused
+        sudo(res.deps.as_mut(), env.clone(), ack(7, false)).unwrap_err();
+
+        // A failed ack or a timeout sends the returned output to the refund address
+        for (sequence, msg) in [
+            (8, ack(8, false)),
+            (
+                9,
+                SudoMsg::IbcLifecycleComplete(IbcLifecycleComplete::IbcTimeout {
+                    channel: String::from("channel-0"),
+                    sequence: 9,
+                }),
+            ),
+        ] {
+            let _res = execute(
+                res.deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::IbcSwap {
+                    min_output_amount: Uint128::new(1),
+                    destination: forward.clone(),
+                    refund_address: Some(Addr::unchecked("refund")),
+                    expiration: None,
+                },
+            )
+            .unwrap();
+            reply(res.deps.as_mut(), env.clone(), transfer_reply(sequence)).unwrap();
+            let forward = PENDING_FORWARDS
+                .load(&res.deps.storage, ("channel-0", sequence as u64))
+                .unwrap();
+
+            let _res = sudo(res.deps.as_mut(), env.clone(), msg).unwrap();
+            assert_eq!(
+                _res.messages,
+                vec![SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+                    to_address: String::from("refund"),
+                    amount: vec![forward.amount],
+                }))]
+            );
+        }
+    }
+
+    #[test]
+    fn test_ibc_swap_forward_cw20_output() {
+        let mut res = proper_initialization();
+        seed_reserves(
+            &mut res,
+            Denom::Cw20(Addr::unchecked("quote")),
+            Uint128::new(100_000),
+        );
+
+        // A cw20 output cannot be sent over ICS-20
+        let info = mock_info("ibc_hooks_sender", &coins(10_000, "native"));
+        let _err = execute(
+            res.deps.as_mut(),
+            mock_env(),
+            info,
+            ExecuteMsg::IbcSwap {
+                min_output_amount: Uint128::zero(),
+                destination: IbcSwapDestination::Ibc {
+                    channel_id: String::from("channel-0"),
+                    receiver: String::from("osmo1receiver"),
+                    timeout_seconds: 600,
+                },
+                refund_address: None,
+                expiration: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::IbcForwardNotNative {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
 }
@@ -1,14 +1,24 @@
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, TokenInfo, TokenListResponse};
+use crate::msg::{
+    confusable_skeleton, normalize_name, validate_name, validate_symbol, AddrUpdate, ExecuteMsg,
+    InitialPool, InstantiateMsg, QueryMsg, ReservedSymbolsResponse, SudoMsg, TokenInfo,
+    TokenListResponse, TokenResponse, VestingResponse, VestingsResponse,
+};
 use crate::state::{
-    entries, Config, Entry, TempEntry, DEFAULT_LIMIT, ENTRY_SEQ, INIT_CONFIG, MAX_LIMIT,
-    TEMP_ENTRY_STATE,
+    entries, Config, Entry, FeeSplit, MigrationStatus, PendingMigration, PoolCodeIds, TempEntry,
+    Vesting, DEFAULT_LIMIT, ENTRY_SEQ, INIT_CONFIG, MAX_LIMIT, PENDING_ENTRIES, PENDING_LIQUIDITY,
+    PENDING_MIGRATIONS, PENDING_POOLS, REPLY_SEQ, RESERVED_SYMBOLS, VESTINGS,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Reply, ReplyOn,
-    Response, StdError, StdResult, Storage, SubMsg, WasmMsg,
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Order, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, SubMsgResult, Uint128,
+    WasmMsg,
+};
+use cw20::{
+    BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg, Denom, DownloadLogoResponse,
+    EmbeddedLogo, Logo, LogoInfo, MarketingInfoResponse, TokenInfoResponse,
 };
 use cw_storage_plus::Bound;
 use cw_utils::parse_reply_instantiate_data;
@@ -16,22 +26,39 @@ use cw_utils::parse_reply_instantiate_data;
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:token-manager";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
-const INSTANTIATE_REPLY_ID: u64 = 1u64;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     //Store the contract name and version
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+
     let config = Config {
-        token_creation_fee: msg.token_creation_fee,
+        token_creation_fees: msg.token_creation_fees,
         token_code_id: msg.token_code_id,
+        admin: admin.clone(),
+        fee_split: msg.fee_split.unwrap_or_default(),
+        treasury_addr: validate_optional_addr(deps.as_ref(), msg.treasury_addr)?,
+        burn_contract_addr: validate_optional_addr(deps.as_ref(), msg.burn_contract_addr)?,
+        curator: validate_optional_addr(deps.as_ref(), msg.curator)?
+            .unwrap_or_else(|| admin.clone()),
+        pool_code_ids: msg.pool_code_ids,
     };
+    validate_fee_config(&config)?;
+
+    // save the reserved symbols
+    for symbol in msg.reserved_symbols.unwrap_or_default() {
+        RESERVED_SYMBOLS.save(deps.storage, &confusable_skeleton(&symbol), &symbol)?;
+    }
 
     // save INIT_CONFIG state
     INIT_CONFIG.save(deps.storage, &config)?;
@@ -46,30 +73,633 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _: Env,
+    env: Env,
     msg_info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::CreateToken { token_info } => {
-            execute_create_new_token(deps, msg_info, token_info)
+        ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades,
+        } => execute_create_new_token(
+            deps,
+            env,
+            msg_info,
+            token_info,
+            managed_upgrades.unwrap_or(false),
+        ),
+        ExecuteMsg::OptOutOfUpgrades { symbol } => {
+            execute_opt_out_of_upgrades(deps, msg_info, symbol)
+        }
+        ExecuteMsg::Claim { symbol } => execute_claim(deps, env, msg_info, symbol),
+        ExecuteMsg::UpdateEntry { symbol } => execute_update_entry(deps, msg_info, symbol),
+        ExecuteMsg::SetVerified { symbol, verified } => {
+            execute_set_verified(deps, msg_info, symbol, verified)
+        }
+        ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees,
+            fee_split,
+            treasury_addr,
+            burn_contract_addr,
+        } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_fee_config(
+                deps,
+                token_creation_fees,
+                fee_split,
+                treasury_addr,
+                burn_contract_addr,
+            )
+        }
+        ExecuteMsg::UpdateCodeId { token_code_id } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_code_id(deps, token_code_id)
         }
+        ExecuteMsg::UpdatePoolCodeIds { pool_code_ids } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_pool_code_ids(deps, pool_code_ids)
+        }
+        ExecuteMsg::UpdateAdmin { admin } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_admin(deps, admin)
+        }
+        ExecuteMsg::UpdateCurator { curator } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_curator(deps, curator)
+        }
+        ExecuteMsg::UpdateReservedSymbols { add, remove } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_update_reserved_symbols(deps, add, remove)
+        }
+        ExecuteMsg::MigrateTokens {
+            code_id,
+            migrate_msg,
+            start_after,
+            limit,
+        } => {
+            assert_admin(deps.as_ref(), &msg_info)?;
+            sudo_migrate_tokens(deps, code_id, migrate_msg, start_after, limit)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateFeeConfig {
+            token_creation_fees,
+            fee_split,
+            treasury_addr,
+            burn_contract_addr,
+        } => sudo_update_fee_config(
+            deps,
+            token_creation_fees,
+            fee_split,
+            treasury_addr,
+            burn_contract_addr,
+        ),
+        SudoMsg::UpdateCodeId { token_code_id } => sudo_update_code_id(deps, token_code_id),
+        SudoMsg::UpdatePoolCodeIds { pool_code_ids } => {
+            sudo_update_pool_code_ids(deps, pool_code_ids)
+        }
+        SudoMsg::UpdateAdmin { admin } => sudo_update_admin(deps, admin),
+        SudoMsg::UpdateCurator { curator } => sudo_update_curator(deps, curator),
+        SudoMsg::UpdateReservedSymbols { add, remove } => {
+            sudo_update_reserved_symbols(deps, add, remove)
+        }
+        SudoMsg::MigrateTokens {
+            code_id,
+            migrate_msg,
+            start_after,
+            limit,
+        } => sudo_migrate_tokens(deps, code_id, migrate_msg, start_after, limit),
+    }
+}
+
+// Make sure the caller is the admin set in the config
+fn assert_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn validate_optional_addr(deps: Deps, addr: Option<String>) -> StdResult<Option<Addr>> {
+    addr.map(|addr| deps.api.addr_validate(&addr)).transpose()
+}
+
+fn apply_addr_update(deps: Deps, update: AddrUpdate) -> StdResult<Option<Addr>> {
+    match update {
+        AddrUpdate::Set { address } => Ok(Some(deps.api.addr_validate(&address)?)),
+        AddrUpdate::Clear {} => Ok(None),
+    }
+}
+
+/**
+ * Check that every accepted fee is non zero with a single coin per denom,
+ * that the fee_split shares add up to 1 and that every non zero share has
+ * somewhere to go
+ */
+fn validate_fee_config(config: &Config) -> Result<(), ContractError> {
+    let fees = &config.token_creation_fees;
+    if fees.is_empty() {
+        return Err(ContractError::InvalidTokenCreationFees {});
+    }
+    for (i, fee) in fees.iter().enumerate() {
+        if fee.amount.is_zero() || fees[i + 1..].iter().any(|f| f.denom == fee.denom) {
+            return Err(ContractError::InvalidTokenCreationFees {});
+        }
+    }
+
+    let split = &config.fee_split;
+    if split.burn + split.treasury + split.burn_contract != Decimal::one() {
+        return Err(ContractError::InvalidFeeSplit {});
+    }
+    if !split.treasury.is_zero() && config.treasury_addr.is_none() {
+        return Err(ContractError::MissingTreasuryAddress {});
+    }
+    if !split.burn_contract.is_zero() && config.burn_contract_addr.is_none() {
+        return Err(ContractError::MissingBurnContractAddress {});
     }
+
+    Ok(())
+}
+
+/**
+ * Find the first accepted fee covered by the funds sent
+ * @return the fee to collect and the rest of the funds to refund
+ */
+fn get_creation_fee(config: &Config, funds: &[Coin]) -> Result<(Coin, Vec<Coin>), ContractError> {
+    let fee = config
+        .token_creation_fees
+        .iter()
+        .find(|fee| {
+            funds
+                .iter()
+                .any(|coin| coin.denom == fee.denom && coin.amount >= fee.amount)
+        })
+        .cloned()
+        .ok_or(ContractError::IncorrectTokenCreationFee {})?;
+
+    let refund = funds
+        .iter()
+        .map(|coin| {
+            let amount = if coin.denom == fee.denom {
+                coin.amount - fee.amount
+            } else {
+                coin.amount
+            };
+            Coin {
+                denom: coin.denom.clone(),
+                amount,
+            }
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+
+    Ok((fee, refund))
+}
+
+/**
+ * Remove the base_amount of base_denom of the initial pool from the funds sent
+ */
+fn deduct_initial_liquidity(
+    funds: &[Coin],
+    base_denom: &str,
+    initial_pool: &InitialPool,
+) -> Result<Vec<Coin>, ContractError> {
+    let mut funds = funds.to_vec();
+    let coin = funds
+        .iter_mut()
+        .find(|coin| coin.denom == base_denom)
+        .ok_or(ContractError::InsufficientInitialLiquidity {})?;
+    coin.amount = coin
+        .amount
+        .checked_sub(initial_pool.base_amount)
+        .map_err(|_| ContractError::InsufficientInitialLiquidity {})?;
+
+    Ok(funds)
+}
+
+/**
+ * Split the fee between the treasury, the burn contract and the burn,
+ * rounding errors are burned
+ */
+fn get_fee_msgs(config: &Config, fee: Coin) -> Vec<CosmosMsg> {
+    let treasury_amount = fee.amount * config.fee_split.treasury;
+    let burn_contract_amount = fee.amount * config.fee_split.burn_contract;
+    let burn_amount = fee.amount - treasury_amount - burn_contract_amount;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let coins = |amount: Uint128| {
+        vec![Coin {
+            denom: fee.denom.clone(),
+            amount,
+        }]
+    };
+
+    if !burn_amount.is_zero() {
+        msgs.push(
+            BankMsg::Burn {
+                amount: coins(burn_amount),
+            }
+            .into(),
+        );
+    }
+    if let (Some(addr), false) = (&config.treasury_addr, treasury_amount.is_zero()) {
+        msgs.push(
+            BankMsg::Send {
+                to_address: addr.to_string(),
+                amount: coins(treasury_amount),
+            }
+            .into(),
+        );
+    }
+    if let (Some(addr), false) = (&config.burn_contract_addr, burn_contract_amount.is_zero()) {
+        msgs.push(
+            BankMsg::Send {
+                to_address: addr.to_string(),
+                amount: coins(burn_contract_amount),
+            }
+            .into(),
+        );
+    }
+
+    msgs
+}
+
+fn sudo_update_fee_config(
+    deps: DepsMut,
+    token_creation_fees: Option<Vec<Coin>>,
+    fee_split: Option<FeeSplit>,
+    treasury_addr: Option<AddrUpdate>,
+    burn_contract_addr: Option<AddrUpdate>,
+) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+
+    if let Some(token_creation_fees) = token_creation_fees {
+        config.token_creation_fees = token_creation_fees;
+    }
+    if let Some(fee_split) = fee_split {
+        config.fee_split = fee_split;
+    }
+    if let Some(update) = treasury_addr {
+        config.treasury_addr = apply_addr_update(deps.as_ref(), update)?;
+    }
+    if let Some(update) = burn_contract_addr {
+        config.burn_contract_addr = apply_addr_update(deps.as_ref(), update)?;
+    }
+
+    validate_fee_config(&config)?;
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("method", "sudo_update_fee_config"))
+}
+
+/**
+ * Tokens created from now on are instantiated from token_code_id,
+ * existing entries keep the code_id they were created with
+ */
+fn sudo_update_code_id(deps: DepsMut, token_code_id: u64) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+    config.token_code_id = token_code_id;
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_update_code_id")
+        .add_attribute("token_code_id", token_code_id.to_string()))
+}
+
+/**
+ * Initial pools created from now on are instantiated from pool_code_ids,
+ * None disables them
+ */
+fn sudo_update_pool_code_ids(
+    deps: DepsMut,
+    pool_code_ids: Option<PoolCodeIds>,
+) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+    config.pool_code_ids = pool_code_ids;
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("method", "sudo_update_pool_code_ids"))
+}
+
+fn sudo_update_admin(deps: DepsMut, admin: String) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+    config.admin = deps.api.addr_validate(&admin)?;
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_update_admin")
+        .add_attribute("admin", admin))
+}
+
+fn sudo_update_curator(deps: DepsMut, curator: String) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+    config.curator = deps.api.addr_validate(&curator)?;
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "sudo_update_curator")
+        .add_attribute("curator", curator))
+}
+
+fn sudo_update_reserved_symbols(
+    deps: DepsMut,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    for symbol in remove {
+        RESERVED_SYMBOLS.remove(deps.storage, &confusable_skeleton(&symbol));
+    }
+    for symbol in add {
+        RESERVED_SYMBOLS.save(deps.storage, &confusable_skeleton(&symbol), &symbol)?;
+    }
+
+    Ok(Response::new().add_attribute("method", "sudo_update_reserved_symbols"))
+}
+
+/**
+ * Refresh the name and logo of an entry from the cw20 TokenInfo and
+ * MarketingInfo queries, an embedded logo is downloaded from the token.
+ * Only the creator of the token or its marketing address can do this
+ */
+fn execute_update_entry(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let symbol = symbol.to_lowercase();
+    let mut entry =
+        entries()
+            .may_load(deps.storage, &symbol)?
+            .ok_or(ContractError::TokenNotFound {
+                symbol: symbol.clone(),
+            })?;
+
+    let marketing_info: MarketingInfoResponse = deps
+        .querier
+        .query_wasm_smart(&entry.contract_addr, &Cw20QueryMsg::MarketingInfo {})?;
+    if info.sender != entry.creator && Some(&info.sender) != marketing_info.marketing.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // The name must stay unique among the entries
+    let token_info: TokenInfoResponse = deps
+        .querier
+        .query_wasm_smart(&entry.contract_addr, &Cw20QueryMsg::TokenInfo {})?;
+
+    // A migrated token has to follow the same rules as at creation and
+    // stay under the symbol it was registered with
+    validate_name(&token_info.name)?;
+    validate_symbol(&token_info.symbol)?;
+    if token_info.symbol.to_lowercase() != entry.symbol {
+        return Err(ContractError::SymbolChanged {
+            symbol: entry.symbol,
+            new_symbol: token_info.symbol,
+        });
+    }
+    let (previous_name, previous_logo) = (entry.name.clone(), entry.logo.clone());
+
+    let name = token_info.name.to_lowercase();
+    if name != entry.name {
+        let existing = entries().idx.name.item(deps.storage, name.clone())?;
+        if existing.is_some() {
+            return Err(ContractError::TokenWithNameAlreadyExists { name });
+        }
+        let existing = entries()
+            .idx
+            .name_skeleton
+            .item(deps.storage, confusable_skeleton(&name))?;
+        if let Some((_, existing)) = existing.filter(|(_, e)| e.symbol != entry.symbol) {
+            return Err(ContractError::ConfusableName {
+                name,
+                existing: existing.name,
+            });
+        }
+        entry.name = name;
+    }
+
+    match marketing_info.logo {
+        Some(LogoInfo::Url(url)) => entry.logo = Logo::Url(url),
+        Some(LogoInfo::Embedded) => {
+            let logo: DownloadLogoResponse = deps
+                .querier
+                .query_wasm_smart(&entry.contract_addr, &Cw20QueryMsg::DownloadLogo {})?;
+            entry.logo = match logo.mime_type.as_str() {
+                "image/svg+xml" => Logo::Embedded(EmbeddedLogo::Svg(logo.data)),
+                _ => Logo::Embedded(EmbeddedLogo::Png(logo.data)),
+            };
+        }
+        // keep the logo the token was created with
+        None => {}
+    }
+
+    // The curator verified the previous name and logo, not the new ones
+    if entry.name != previous_name || entry.logo != previous_logo {
+        entry.verified = false;
+    }
+
+    entries().save(deps.storage, &symbol, &entry)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_update_entry")
+        .add_attribute("symbol", symbol))
+}
+
+/**
+ * Issue a WasmMsg::Migrate to code_id for every managed token of the page
+ * that is not on it yet. Each migration is a submessage replying on success
+ * and error, so a token that fails to migrate is recorded as failed without
+ * reverting the rest of the batch, and is skipped by the next batches to
+ * the same code_id. The last_symbol attribute, set unless the page is empty, is the
+ * start_after of the next page
+ */
+fn sudo_migrate_tokens(
+    deps: DepsMut,
+    code_id: Option<u64>,
+    migrate_msg: Binary,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    let code_id = code_id.unwrap_or(config.token_code_id);
+    let start_after = start_after.map(|s| s.to_lowercase());
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let to_migrate: StdResult<Vec<_>> = entries()
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, entry)) => {
+                entry.managed_upgrades
+                    && entry.code_id != code_id
+                    && !matches!(
+                        entry.migration,
+                        Some(MigrationStatus::Failed { code_id: failed, .. }) if failed == code_id
+                    )
+            }
+            Err(_) => true,
+        })
+        .take(limit)
+        .collect();
+
+    let to_migrate = to_migrate?;
+    let last_symbol = to_migrate.last().map(|(symbol, _)| symbol.clone());
+
+    let mut sub_msgs: Vec<SubMsg> = vec![];
+    for (symbol, entry) in to_migrate {
+        let reply_id = next_reply_seq(deps.storage)?;
+        PENDING_MIGRATIONS.save(
+            deps.storage,
+            reply_id,
+            &PendingMigration { symbol, code_id },
+        )?;
+
+        sub_msgs.push(SubMsg {
+            gas_limit: None,
+            id: reply_id,
+            reply_on: ReplyOn::Always,
+            msg: WasmMsg::Migrate {
+                contract_addr: entry.contract_addr.to_string(),
+                new_code_id: code_id,
+                msg: migrate_msg.clone(),
+            }
+            .into(),
+        });
+    }
+
+    let mut res = Response::new()
+        .add_attribute("method", "sudo_migrate_tokens")
+        .add_attribute("code_id", code_id.to_string())
+        .add_attribute("count", sub_msgs.len().to_string());
+    if let Some(last_symbol) = last_symbol {
+        res = res.add_attribute("last_symbol", last_symbol);
+    }
+    Ok(res.add_submessages(sub_msgs))
+}
+
+fn execute_opt_out_of_upgrades(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let symbol = symbol.to_lowercase();
+    let mut entry =
+        entries()
+            .may_load(deps.storage, &symbol)?
+            .ok_or(ContractError::TokenNotFound {
+                symbol: symbol.clone(),
+            })?;
+    if info.sender != entry.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !entry.managed_upgrades {
+        return Err(ContractError::TokenNotManaged { symbol });
+    }
+
+    entry.managed_upgrades = false;
+    entries().save(deps.storage, &symbol, &entry)?;
+
+    // Give the wasm admin of the token back to its creator
+    let msg = WasmMsg::UpdateAdmin {
+        contract_addr: entry.contract_addr.to_string(),
+        admin: entry.creator.to_string(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_opt_out_of_upgrades")
+        .add_attribute("symbol", symbol)
+        .add_message(msg))
+}
+
+/**
+ * Send the tokens vested so far and not claimed yet to the caller
+ */
+fn execute_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    let symbol = symbol.to_lowercase();
+    let key = (&info.sender, symbol.as_str());
+    let mut vesting =
+        VESTINGS
+            .may_load(deps.storage, key)?
+            .ok_or(ContractError::VestingNotFound {
+                recipient: info.sender.to_string(),
+                symbol: symbol.clone(),
+            })?;
+
+    let amount = vesting.claimable(env.block.time);
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    vesting.claimed += amount;
+    VESTINGS.save(deps.storage, key, &vesting)?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: vesting.token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: info.sender.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_claim")
+        .add_attribute("symbol", symbol)
+        .add_attribute("amount", amount)
+        .add_message(msg))
+}
+
+fn execute_set_verified(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    verified: bool,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    if info.sender != config.curator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let symbol = symbol.to_lowercase();
+    let mut entry =
+        entries()
+            .may_load(deps.storage, &symbol)?
+            .ok_or(ContractError::TokenNotFound {
+                symbol: symbol.clone(),
+            })?;
+    entry.verified = verified;
+    entries().save(deps.storage, &symbol, &entry)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_verified")
+        .add_attribute("symbol", symbol)
+        .add_attribute("verified", verified.to_string()))
 }
 
 fn execute_create_new_token(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    token_info: TokenInfo,
+    mut token_info: TokenInfo,
+    managed_upgrades: bool,
 ) -> Result<Response, ContractError> {
     let config = INIT_CONFIG.load(deps.storage)?;
 
-    // Check if the token_info is valid
+    // The token is created with the normalized name, then check if the token_info is valid
+    token_info.name = normalize_name(&token_info.name);
     token_info.validate()?;
 
     // Check if a token with the same symbol already exists
     let entry = entries().may_load(deps.storage, &token_info.symbol.to_lowercase())?;
-    if let Some(_) = entry {
+    if entry.is_some() {
         return Err(ContractError::TokenWithSymbolAlreadyExists {
             symbol: token_info.symbol,
         });
@@ -80,47 +710,132 @@ fn execute_create_new_token(
         .idx
         .name
         .item(deps.storage, token_info.name.to_lowercase())?;
-    if let Some(_) = entry {
+    if entry.is_some() {
         return Err(ContractError::TokenWithNameAlreadyExists {
             name: token_info.name,
         });
     }
 
-    // Check if the amount sent by the caller is equal to the token_creation_fee
-    let coin = info.funds.iter().find(|coin| {
-        coin.denom == config.token_creation_fee.denom
-            && coin.amount == config.token_creation_fee.amount
-    });
+    // Check the symbol isn't reserved or confusable with another one
+    let symbol_skeleton = confusable_skeleton(&token_info.symbol);
+    if RESERVED_SYMBOLS.has(deps.storage, &symbol_skeleton) {
+        return Err(ContractError::ReservedSymbol {
+            symbol: token_info.symbol,
+        });
+    }
+    let entry = entries()
+        .idx
+        .symbol_skeleton
+        .item(deps.storage, symbol_skeleton)?;
+    if let Some((_, existing)) = entry {
+        return Err(ContractError::ConfusableSymbol {
+            symbol: token_info.symbol,
+            existing: existing.symbol,
+        });
+    }
 
-    let fee = match coin {
-        Some(coin) => coin.clone(),
-        None => {
-            return Err(ContractError::IncorrectTokenCreationFee {});
+    // Check the name isn't confusable with another one
+    let entry = entries()
+        .idx
+        .name_skeleton
+        .item(deps.storage, confusable_skeleton(&token_info.name))?;
+    if let Some((_, existing)) = entry {
+        return Err(ContractError::ConfusableName {
+            name: token_info.name,
+            existing: existing.name,
+        });
+    }
+
+    // Check the vesting schedules, a recipient can only have one allocation
+    let mut recipients: Vec<Addr> = vec![];
+    for allocation in token_info.vesting.iter() {
+        let recipient = deps.api.addr_validate(&allocation.recipient)?;
+        if recipients.contains(&recipient)
+            || allocation.amount.is_zero()
+            || allocation.duration_seconds == 0
+            || allocation.cliff_seconds > allocation.duration_seconds
+        {
+            return Err(ContractError::InvalidVesting {});
+        }
+        recipients.push(recipient);
+    }
+
+    // Set aside the base liquidity of the initial pool before looking for the fee
+    let funds = match &token_info.initial_pool {
+        Some(initial_pool) => {
+            let pool_code_ids = config
+                .pool_code_ids
+                .as_ref()
+                .ok_or(ContractError::InitialPoolDisabled {})?;
+            if initial_pool.base_amount.is_zero() || initial_pool.quote_amount.is_zero() {
+                return Err(ContractError::InvalidInitialPool {});
+            }
+            deduct_initial_liquidity(&info.funds, &pool_code_ids.base_denom, initial_pool)?
         }
+        None => info.funds.clone(),
     };
 
-    // Save the TempEntry to state
+    // Check if the funds sent by the caller cover one of the token_creation_fees
+    let (fee, refund) = get_creation_fee(&config, &funds)?;
+
+    // Save the TempEntry to state under a fresh reply id
+    let reply_id = next_reply_seq(deps.storage)?;
     let entry = TempEntry {
         name: token_info.name.to_lowercase(),
         symbol: token_info.symbol.to_lowercase(),
         logo: token_info.marketing.logo.clone(),
+        creator: info.sender.clone(),
+        code_id: config.token_code_id,
+        managed_upgrades,
+        initial_pool: token_info.initial_pool.clone(),
+        vesting: token_info.vesting.clone(),
     };
-    TEMP_ENTRY_STATE.save(deps.storage, &entry)?;
+    PENDING_ENTRIES.save(deps.storage, reply_id, &entry)?;
 
-    // Add message to burn the token_creation_fee
-    let amount = vec![fee.clone()];
-    let burn_msg = BankMsg::Burn { amount };
-    let msgs: Vec<CosmosMsg> = vec![burn_msg.into()];
+    // Add messages to route the token_creation_fee and refund any overpayment
+    let mut msgs = get_fee_msgs(&config, fee);
+    if !refund.is_empty() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: refund,
+            }
+            .into(),
+        );
+    }
+
+    // The manager mints the quote liquidity of the initial pool and the
+    // vesting allocations to itself
+    let mut cw20_msg = token_info.clone();
+    let mut manager_amount = Uint128::zero();
+    if let Some(initial_pool) = cw20_msg.initial_pool.take() {
+        manager_amount += initial_pool.quote_amount;
+    }
+    for allocation in std::mem::take(&mut cw20_msg.vesting) {
+        manager_amount += allocation.amount;
+    }
+    if !manager_amount.is_zero() {
+        cw20_msg.initial_balances.push(Cw20Coin {
+            address: env.contract.address.to_string(),
+            amount: manager_amount,
+        });
+    }
+
+    // The manager stays the wasm admin of the token when it handles its upgrades
+    let admin = match managed_upgrades {
+        true => env.contract.address,
+        false => info.sender,
+    };
 
     // Add wasm msg to create new cw20 token instance from config.token_code_id
     let sub_msg = SubMsg {
         gas_limit: None,
-        id: INSTANTIATE_REPLY_ID,
+        id: reply_id,
         reply_on: ReplyOn::Success,
         msg: WasmMsg::Instantiate {
-            admin: Some(info.sender.to_string()),
+            admin: Some(admin.to_string()),
             code_id: config.token_code_id,
-            msg: to_binary(&token_info)?,
+            msg: to_binary(&cw20_msg)?,
             funds: vec![],
             label: token_info.name.to_string(),
         }
@@ -139,21 +854,26 @@ fn execute_create_new_token(
 
 /**
  * Handle reply for execute_create_new_token
- * Load the TEMP_ENTRY_STATE and use the data to create a new Entry that includes
- * the newly created contract_address and latest ENTRY_SEQ id,
+ * Load the PENDING_ENTRIES item of the reply id and use the data to create a new
+ * Entry that includes the newly created contract_address and latest ENTRY_SEQ id,
  *
  * Save it to the store under (name, symbol): Entry of fn entries();
  * @return the token_contract_addr as an attribute on success
  */
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
     match msg.id {
-        INSTANTIATE_REPLY_ID => handle_instantiate_reply(deps, msg),
+        id if PENDING_ENTRIES.has(deps.storage, id) => handle_instantiate_reply(deps, env, msg),
+        id if PENDING_MIGRATIONS.has(deps.storage, id) => handle_migrate_reply(deps, msg),
+        id if PENDING_POOLS.has(deps.storage, id) => handle_pool_instantiate_reply(deps, msg),
+        id if PENDING_LIQUIDITY.has(deps.storage, id) => handle_add_liquidity_reply(deps, env, msg),
         id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
     }
 }
 
-fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+fn handle_instantiate_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let reply_id = msg.id;
+
     // Get data from reply msg
     // See: https://github.com/CosmWasm/cw-plus/blob/main/packages/utils/src/parse_reply.rs
     let res = parse_reply_instantiate_data(msg);
@@ -164,10 +884,19 @@ fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
         }
     };
 
-    // Get temp_entry and next id
-    let temp_entry = TEMP_ENTRY_STATE.load(deps.storage)?;
+    // Get temp_entry, remove it from the pending creations and get the next id
+    let temp_entry = PENDING_ENTRIES.load(deps.storage, reply_id)?;
+    PENDING_ENTRIES.remove(deps.storage, reply_id);
     let id = next_entry_seq(deps.storage)?;
 
+    // Another creation with the same symbol may have completed since this one started
+    if entries().has(deps.storage, temp_entry.symbol.as_str()) {
+        return Err(StdError::generic_err(format!(
+            "Token with symbol: {:?} already exists",
+            temp_entry.symbol
+        )));
+    }
+
     // Save the actual Entry
     let entry = Entry {
         id,
@@ -175,10 +904,186 @@ fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
         name: temp_entry.name,
         symbol: temp_entry.symbol,
         logo: temp_entry.logo,
+        creator: temp_entry.creator,
+        code_id: temp_entry.code_id,
+        verified: false,
+        managed_upgrades: temp_entry.managed_upgrades,
+        migration: None,
+        pool_addr: None,
     };
     entries().save(deps.storage, entry.symbol.as_str(), &entry)?;
 
-    Ok(Response::new().add_attribute("token_contract_addr", data.contract_address))
+    // Start the vesting of the allocations held by the manager
+    let start_time = env.block.time;
+    for allocation in temp_entry.vesting {
+        let recipient = deps.api.addr_validate(&allocation.recipient)?;
+        let vesting = Vesting {
+            symbol: entry.symbol.clone(),
+            token: entry.contract_addr.clone(),
+            amount: allocation.amount,
+            claimed: Uint128::zero(),
+            start_time,
+            cliff_time: start_time.plus_seconds(allocation.cliff_seconds),
+            end_time: start_time.plus_seconds(allocation.duration_seconds),
+        };
+        VESTINGS.save(deps.storage, (&recipient, entry.symbol.as_str()), &vesting)?;
+    }
+
+    let mut res = Response::new().add_attribute("token_contract_addr", data.contract_address);
+
+    // Chain the instantiation of the initial pool with the new token as quote
+    if let Some(initial_pool) = temp_entry.initial_pool {
+        let pool_code_ids = INIT_CONFIG
+            .load(deps.storage)?
+            .pool_code_ids
+            .ok_or_else(|| StdError::not_found("pool_code_ids"))?;
+        let reply_id = next_reply_seq(deps.storage)?;
+        let msg = token_swap::msg::InstantiateMsg {
+            native_denom: Denom::Native(pool_code_ids.base_denom.clone()),
+            base_denom: Denom::Native(pool_code_ids.base_denom.clone()),
+            quote_denom: Denom::Cw20(entry.contract_addr.clone()),
+            lp_token_code_id: pool_code_ids.lp_token_code_id,
+        };
+        let sub_msg = SubMsg {
+            gas_limit: None,
+            id: reply_id,
+            reply_on: ReplyOn::Success,
+            msg: WasmMsg::Instantiate {
+                admin: None,
+                code_id: pool_code_ids.swap_code_id,
+                msg: to_binary(&msg)?,
+                funds: vec![],
+                label: format!("{} pool", entry.symbol),
+            }
+            .into(),
+        };
+        PENDING_POOLS.save(deps.storage, reply_id, &(entry.symbol, initial_pool))?;
+        res = res.add_submessage(sub_msg);
+    }
+
+    Ok(res)
+}
+
+/**
+ * Handle reply for the initial pool instantiation
+ * Record the pool on the Entry then approve the quote liquidity and add it
+ * to the pool along with the base liquidity
+ */
+fn handle_pool_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    let reply_id = msg.id;
+    let data = parse_reply_instantiate_data(msg)
+        .map_err(|_| StdError::generic_err("Error parsing data"))?;
+
+    let (symbol, initial_pool) = PENDING_POOLS.load(deps.storage, reply_id)?;
+    PENDING_POOLS.remove(deps.storage, reply_id);
+    let base_denom = INIT_CONFIG
+        .load(deps.storage)?
+        .pool_code_ids
+        .ok_or_else(|| StdError::not_found("pool_code_ids"))?
+        .base_denom;
+
+    let pool_addr = deps.api.addr_validate(&data.contract_address)?;
+    let mut entry = entries().load(deps.storage, &symbol)?;
+    entry.pool_addr = Some(pool_addr.clone());
+    entries().save(deps.storage, &symbol, &entry)?;
+
+    let allowance_msg = WasmMsg::Execute {
+        contract_addr: entry.contract_addr.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::IncreaseAllowance {
+            spender: pool_addr.to_string(),
+            amount: initial_pool.quote_amount,
+            expires: None,
+        })?,
+        funds: vec![],
+    };
+
+    let reply_id = next_reply_seq(deps.storage)?;
+    let add_liquidity_msg = SubMsg {
+        gas_limit: None,
+        id: reply_id,
+        reply_on: ReplyOn::Success,
+        msg: WasmMsg::Execute {
+            contract_addr: pool_addr.to_string(),
+            msg: to_binary(&token_swap::msg::ExecuteMsg::AddLiquidity {
+                base_token_amount: initial_pool.base_amount,
+                max_quote_token_amount: initial_pool.quote_amount,
+                expiration: None,
+            })?,
+            funds: vec![Coin {
+                denom: base_denom,
+                amount: initial_pool.base_amount,
+            }],
+        }
+        .into(),
+    };
+    PENDING_LIQUIDITY.save(deps.storage, reply_id, &symbol)?;
+
+    Ok(Response::new()
+        .add_attribute("pool_contract_addr", pool_addr)
+        .add_message(allowance_msg)
+        .add_submessage(add_liquidity_msg))
+}
+
+/**
+ * Handle reply for the initial liquidity
+ * Send the LP tokens minted to the manager to the creator of the token
+ */
+fn handle_add_liquidity_reply(deps: DepsMut, env: Env, msg: Reply) -> StdResult<Response> {
+    let symbol = PENDING_LIQUIDITY.load(deps.storage, msg.id)?;
+    PENDING_LIQUIDITY.remove(deps.storage, msg.id);
+
+    let entry = entries().load(deps.storage, &symbol)?;
+    let pool_addr = entry
+        .pool_addr
+        .ok_or_else(|| StdError::not_found("pool_addr"))?;
+
+    let info: token_swap::msg::InfoResponse = deps
+        .querier
+        .query_wasm_smart(&pool_addr, &token_swap::msg::QueryMsg::Info {})?;
+    let balance: BalanceResponse = deps.querier.query_wasm_smart(
+        &info.lp_token_address,
+        &Cw20QueryMsg::Balance {
+            address: env.contract.address.to_string(),
+        },
+    )?;
+
+    let transfer_msg = WasmMsg::Execute {
+        contract_addr: info.lp_token_address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: entry.creator.to_string(),
+            amount: balance.balance,
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_attribute("lp_token_amount", balance.balance)
+        .add_message(transfer_msg))
+}
+
+/**
+ * Handle reply for sudo_migrate_tokens
+ * Record the outcome of the migration on the Entry, the code_id of the entry
+ * only moves when the migration succeeded
+ */
+fn handle_migrate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    let pending = PENDING_MIGRATIONS.load(deps.storage, msg.id)?;
+    PENDING_MIGRATIONS.remove(deps.storage, msg.id);
+
+    let mut entry = entries().load(deps.storage, &pending.symbol)?;
+    let code_id = pending.code_id;
+    entry.migration = match msg.result {
+        SubMsgResult::Ok(_) => {
+            entry.code_id = code_id;
+            Some(MigrationStatus::Migrated { code_id })
+        }
+        SubMsgResult::Err(error) => Some(MigrationStatus::Failed { code_id, error }),
+    };
+    entries().save(deps.storage, &pending.symbol, &entry)?;
+
+    Ok(Response::new()
+        .add_attribute("symbol", pending.symbol)
+        .add_attribute("migrated", (entry.code_id == code_id).to_string()))
 }
 
 pub fn next_entry_seq(store: &mut dyn Storage) -> StdResult<u64> {
@@ -187,30 +1092,131 @@ pub fn next_entry_seq(store: &mut dyn Storage) -> StdResult<u64> {
     Ok(id)
 }
 
+pub fn next_reply_seq(store: &mut dyn Storage) -> StdResult<u64> {
+    let id: u64 = REPLY_SEQ.may_load(store)?.unwrap_or_default() + 1;
+    REPLY_SEQ.save(store, &id)?;
+    Ok(id)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     // Match and route the query message to the appropriate handler
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
-        QueryMsg::QueryTokenList { start_after, limit } => {
-            to_binary(&query_tokens_list(deps, start_after, limit)?)
+        QueryMsg::QueryTokenList {
+            start_after,
+            limit,
+            verified,
+        } => to_binary(&query_tokens_list(deps, start_after, limit, verified)?),
+        QueryMsg::TokenBySymbol { symbol } => to_binary(&query_token_by_symbol(deps, symbol)?),
+        QueryMsg::TokenByName { name } => to_binary(&query_token_by_name(deps, name)?),
+        QueryMsg::TokenByAddress { contract_addr } => {
+            to_binary(&query_token_by_address(deps, contract_addr)?)
+        }
+        QueryMsg::TokensByCreator {
+            creator,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens_by_creator(deps, creator, start_after, limit)?),
+        QueryMsg::ReservedSymbols { start_after, limit } => {
+            to_binary(&query_reserved_symbols(deps, start_after, limit)?)
+        }
+        QueryMsg::Vesting { recipient, symbol } => {
+            to_binary(&query_vesting(deps, env, recipient, symbol)?)
         }
+        QueryMsg::VestingsByRecipient {
+            recipient,
+            start_after,
+            limit,
+        } => to_binary(&query_vestings_by_recipient(
+            deps,
+            env,
+            recipient,
+            start_after,
+            limit,
+        )?),
     }
 }
 
+fn query_reserved_symbols(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReservedSymbolsResponse> {
+    let start_after = start_after.map(|s| confusable_skeleton(&s));
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let symbols: StdResult<Vec<_>> = RESERVED_SYMBOLS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, symbol)| symbol))
+        .collect();
+
+    Ok(ReservedSymbolsResponse { symbols: symbols? })
+}
+
+fn to_vesting_response(env: &Env, vesting: Vesting) -> VestingResponse {
+    VestingResponse {
+        vested: vesting.vested(env.block.time),
+        claimable: vesting.claimable(env.block.time),
+        vesting,
+    }
+}
+
+fn query_vesting(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    symbol: String,
+) -> StdResult<VestingResponse> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let vesting = VESTINGS.load(deps.storage, (&recipient, &symbol.to_lowercase()))?;
+    Ok(to_vesting_response(&env, vesting))
+}
+
+fn query_vestings_by_recipient(
+    deps: Deps,
+    env: Env,
+    recipient: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VestingsResponse> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let start_after = start_after.map(|s| s.to_lowercase());
+    let start = start_after.as_deref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let vestings: StdResult<Vec<_>> = VESTINGS
+        .prefix(&recipient)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, vesting)| to_vesting_response(&env, vesting)))
+        .collect();
+
+    Ok(VestingsResponse {
+        vestings: vestings?,
+    })
+}
+
 fn query_tokens_list(
     deps: Deps,
     start_after: Option<u64>,
     limit: Option<u32>,
+    verified: Option<bool>,
 ) -> StdResult<TokenListResponse> {
     let start = start_after.map(Bound::exclusive);
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
 
-    // get the entries that matches the range
+    // get the entries that matches the range and the verified filter
     let entries: StdResult<Vec<_>> = entries()
         .idx
         .id
         .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match (item, verified) {
+            (Ok((_, entry)), Some(verified)) => entry.verified == verified,
+            _ => true,
+        })
         .take(limit)
         .collect();
 
@@ -221,7 +1227,60 @@ fn query_tokens_list(
     Ok(results)
 }
 
-// ? nice to have: query token by name or symbol
+/**
+ * List the tokens created by `creator` ordered by symbol,
+ * `start_after` is the last symbol of the previous page
+ */
+fn query_tokens_by_creator(
+    deps: Deps,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokenListResponse> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let start = start_after.map(|s| Bound::exclusive(s.to_lowercase()));
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let entries: StdResult<Vec<_>> = entries()
+        .idx
+        .creator
+        .prefix(creator)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect();
+
+    Ok(TokenListResponse {
+        entries: entries?.into_iter().map(|l| l.1).collect(),
+    })
+}
+
+/**
+ * Symbols and names are stored in lowercase, so the lookups
+ * below are case insensitive
+ */
+fn query_token_by_symbol(deps: Deps, symbol: String) -> StdResult<TokenResponse> {
+    let entry = entries().may_load(deps.storage, &symbol.to_lowercase())?;
+    Ok(TokenResponse { entry })
+}
+
+fn query_token_by_name(deps: Deps, name: String) -> StdResult<TokenResponse> {
+    let entry = entries()
+        .idx
+        .name
+        .item(deps.storage, name.to_lowercase())?
+        .map(|(_, entry)| entry);
+    Ok(TokenResponse { entry })
+}
+
+fn query_token_by_address(deps: Deps, contract_addr: String) -> StdResult<TokenResponse> {
+    let contract_addr = deps.api.addr_validate(&contract_addr)?;
+    let entry = entries()
+        .idx
+        .contract_addr
+        .item(deps.storage, contract_addr)?
+        .map(|(_, entry)| entry);
+    Ok(TokenResponse { entry })
+}
 
 fn query_config(deps: Deps) -> StdResult<Config> {
     let config = INIT_CONFIG.load(deps.storage)?;
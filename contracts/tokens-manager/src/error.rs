@@ -12,12 +12,87 @@ pub enum ContractError {
     #[error("Incorrect amount sent as token_creation_fee")]
     IncorrectTokenCreationFee {},
 
+    #[error("Token with symbol: {symbol:?} does not exist")]
+    TokenNotFound { symbol: String },
+
+    #[error("Token with symbol: {symbol:?} is not upgraded by the manager")]
+    TokenNotManaged { symbol: String },
+
+    #[error("initial_pool amounts must be non zero")]
+    InvalidInitialPool {},
+
+    #[error("Initial pools are disabled")]
+    InitialPoolDisabled {},
+
+    #[error("Insufficient funds sent for the initial_pool liquidity")]
+    InsufficientInitialLiquidity {},
+
+    #[error("vesting allocations must have distinct recipients, a non zero amount and duration, and a cliff within the duration")]
+    InvalidVesting {},
+
+    #[error("No vesting for {recipient:?} on token with symbol: {symbol:?}")]
+    VestingNotFound { recipient: String, symbol: String },
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+
+    #[error("token_creation_fees must be non zero and have at most one coin per denom")]
+    InvalidTokenCreationFees {},
+
+    #[error("fee_split shares must add up to 1")]
+    InvalidFeeSplit {},
+
+    #[error("fee_split sends a share to the treasury but treasury_addr is not set")]
+    MissingTreasuryAddress {},
+
+    #[error("fee_split sends a share to the burn contract but burn_contract_addr is not set")]
+    MissingBurnContractAddress {},
+
     #[error("Token with symbol: {symbol:?} already exists")]
     TokenWithSymbolAlreadyExists { symbol: String },
 
     #[error("Token with name: {name:?} already exists")]
     TokenWithNameAlreadyExists { name: String },
 
+    #[error("Name must be {min} to {max} characters long")]
+    InvalidNameLength { min: usize, max: usize },
+
+    #[error("Name must be in NFKC normal form")]
+    NonNormalizedName {},
+
+    #[error("Name contains the character {character:?} which is not allowed")]
+    DisallowedCharacterInName { character: char },
+
+    #[error("Name must not mix characters of different scripts")]
+    MixedScriptName {},
+
+    #[error("Name contains the control character {character:?}")]
+    ControlCharacterInName { character: char },
+
+    #[error("Name must not start or end with a space or contain consecutive spaces")]
+    InvalidNameSpacing {},
+
+    #[error("Symbol must be {min} to {max} characters long")]
+    InvalidSymbolLength { min: usize, max: usize },
+
+    #[error("Symbol contains {character:?}, only [a-zA-Z-] are allowed")]
+    InvalidSymbolCharacter { character: char },
+
+    #[error("Decimals must not exceed {max}")]
+    InvalidDecimals { max: u8 },
+
+    #[error("Symbol: {symbol:?} is reserved")]
+    ReservedSymbol { symbol: String },
+
+    #[error("Symbol: {symbol:?} is too similar to the existing symbol {existing:?}")]
+    ConfusableSymbol { symbol: String, existing: String },
+
+    #[error("Name: {name:?} is too similar to the existing name {existing:?}")]
+    ConfusableName { name: String, existing: String },
+
+    #[error("Token with symbol: {symbol:?} now reports the symbol {new_symbol:?}")]
+    SymbolChanged { symbol: String, new_symbol: String },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
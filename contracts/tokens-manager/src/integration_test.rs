@@ -1,15 +1,27 @@
 #[cfg(test)]
 mod tests {
     use crate::msg::{
-        ExecuteMsg, InstantiateMsg, MarketingInfo, QueryMsg, TokenInfo, TokenListResponse,
+        AddrUpdate, ExecuteMsg, InitialPool, InstantiateMsg, MarketingInfo, QueryMsg, SudoMsg,
+        TokenInfo, TokenListResponse, TokenResponse, VestingAllocation, VestingResponse,
+        VestingsResponse,
     };
-    use crate::state::Entry;
+    use crate::state::{Entry, FeeSplit, MigrationStatus, PoolCodeIds};
+    use crate::ContractError;
 
-    use cosmwasm_std::{coins, Addr, Coin, Empty, Uint128};
-    use cw20::Logo;
+    use cosmwasm_std::{
+        coins, to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Deps, Empty, Env, Event,
+        StdResult, Uint128, WasmMsg,
+    };
+    use cw20::{EmbeddedLogo, Logo};
     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
 
     const USER: &str = "user";
+    const OTHER_USER: &str = "other_user";
+    const ADMIN: &str = "admin";
+    const TREASURY: &str = "treasury";
+    const BURN_CONTRACT: &str = "burn_contract";
+    const CURATOR: &str = "curator";
+    const MARKETING: &str = "test";
     const NATIVE_DENOM: &str = "udenom";
     const SUPPLY: u128 = 500_000_000u128;
 
@@ -21,11 +33,47 @@ mod tests {
     }
 
     fn contract_cw20() -> Box<dyn Contract<Empty>> {
-        Box::new(ContractWrapper::new(
-            cw20_base::contract::execute,
-            cw20_base::contract::instantiate,
-            cw20_base::contract::query,
-        ))
+        Box::new(
+            ContractWrapper::new(
+                cw20_base::contract::execute,
+                cw20_base::contract::instantiate,
+                cw20_base::contract::query,
+            )
+            .with_migrate(cw20_base::contract::migrate),
+        )
+    }
+
+    // A cw20 migrated to report another symbol than the one it was created with
+    fn contract_cw20_renamed() -> Box<dyn Contract<Empty>> {
+        fn query(deps: Deps, env: Env, msg: cw20_base::msg::QueryMsg) -> StdResult<Binary> {
+            match msg {
+                cw20_base::msg::QueryMsg::TokenInfo {} => {
+                    let mut token_info = cw20_base::contract::query_token_info(deps)?;
+                    token_info.symbol = "OTHER".to_string();
+                    to_binary(&token_info)
+                }
+                msg => cw20_base::contract::query(deps, env, msg),
+            }
+        }
+        Box::new(
+            ContractWrapper::new(
+                cw20_base::contract::execute,
+                cw20_base::contract::instantiate,
+                query,
+            )
+            .with_migrate(cw20_base::contract::migrate),
+        )
+    }
+
+    fn contract_token_swap() -> Box<dyn Contract<Empty>> {
+        Box::new(
+            ContractWrapper::new(
+                token_swap::contract::execute,
+                token_swap::contract::instantiate,
+                token_swap::contract::query,
+            )
+            .with_reply(token_swap::contract::reply),
+        )
     }
 
     fn contract_template() -> Box<dyn Contract<Empty>> {
@@ -35,7 +83,8 @@ mod tests {
                 crate::contract::instantiate,
                 crate::contract::query,
             )
-            .with_reply(crate::contract::reply),
+            .with_reply(crate::contract::reply)
+            .with_sudo(crate::contract::sudo),
         )
     }
 
@@ -49,9 +98,11 @@ mod tests {
             marketing: MarketingInfo {
                 project: "Test Test Test".to_string(),
                 description: "Testing token".to_string(),
-                marketing: "test".to_string(),
+                marketing: MARKETING.to_string(),
                 logo: Logo::Url("logo_url".to_string()),
             },
+            initial_pool: None,
+            vesting: vec![],
         }
     }
 
@@ -65,6 +116,14 @@ mod tests {
                     coins(SUPPLY, NATIVE_DENOM.to_string()),
                 )
                 .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(OTHER_USER),
+                    coins(SUPPLY, NATIVE_DENOM.to_string()),
+                )
+                .unwrap();
         })
     }
 
@@ -72,6 +131,7 @@ mod tests {
         let msg = QueryMsg::QueryTokenList {
             start_after: None,
             limit: None,
+            verified: None,
         };
         let result: TokenListResponse =
             app.wrap().query_wasm_smart(contract_address, &msg).unwrap();
@@ -82,13 +142,25 @@ mod tests {
         let mut app = mock_app();
         let template_id = app.store_code(contract_template());
         let cw20_id = app.store_code(contract_cw20());
+        let swap_code_id = app.store_code(contract_token_swap());
 
         let msg = InstantiateMsg {
             token_code_id: cw20_id,
-            token_creation_fee: Coin {
+            token_creation_fees: vec![Coin {
                 amount: Uint128::from(100_000_000u128),
                 denom: "udenom".to_string(),
-            },
+            }],
+            admin: Some(ADMIN.to_string()),
+            fee_split: Some(FeeSplit::default()),
+            treasury_addr: None,
+            burn_contract_addr: None,
+            curator: Some(CURATOR.to_string()),
+            reserved_symbols: None,
+            pool_code_ids: Some(PoolCodeIds {
+                swap_code_id,
+                lp_token_code_id: cw20_id,
+                base_denom: NATIVE_DENOM.to_string(),
+            }),
         };
 
         let template_contract_addr = app
@@ -121,8 +193,11 @@ mod tests {
             .execute_contract(
                 Addr::unchecked(USER),
                 _instance.c_addr.clone(),
-                &ExecuteMsg::CreateToken { token_info },
-                &[_instance.msg.token_creation_fee],
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
             )
             .unwrap();
 
@@ -136,9 +211,989 @@ mod tests {
                     name: "test token".to_string(),
                     symbol: "ttt".to_string(),
                     logo: Logo::Url("logo_url".to_string()),
-                    contract_addr: Addr::unchecked("contract1")
+                    contract_addr: Addr::unchecked("contract1"),
+                    creator: Addr::unchecked(USER),
+                    code_id: _instance.msg.token_code_id,
+                    verified: false,
+                    managed_upgrades: false,
+                    migration: None,
+                    pool_addr: None,
                 }]
             }
         );
     }
+
+    #[test]
+    fn test_create_new_token_symbol_charset() {
+        let mut _instance = mock_instantiate();
+
+        // cw20-base itself only accepts letters and dashes in symbols
+        let mut token_info = new_token_info();
+        token_info.symbol = "T3ST".to_string();
+        _instance
+            .app
+            .instantiate_contract(
+                _instance.msg.token_code_id,
+                Addr::unchecked(USER),
+                &token_info,
+                &[],
+                "T3ST",
+                None,
+            )
+            .unwrap_err();
+
+        // so the manager rejects digits before instantiating the token
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::InvalidSymbolCharacter { character: '3' }.to_string()
+        );
+
+        // and every symbol it accepts is accepted by cw20-base
+        let mut token_info = new_token_info();
+        token_info.symbol = "TE-ST".to_string();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+        assert_eq!(get_token(&_instance, "TE-ST").symbol, "te-st");
+    }
+
+    #[test]
+    fn test_create_new_token_unicode_name() {
+        let mut _instance = mock_instantiate();
+
+        // the full width letters are normalized before cw20-base instantiates the token
+        let mut token_info = new_token_info();
+        token_info.name = "Ｊetón Coin".to_string();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+
+        let entry = get_token(&_instance, "TTT");
+        assert_eq!(entry.name, "jetón coin");
+        let token_info: cw20::TokenInfoResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(entry.contract_addr, &cw20::Cw20QueryMsg::TokenInfo {})
+            .unwrap();
+        assert_eq!(token_info.name, "Jetón Coin");
+    }
+
+    #[test]
+    fn test_query_token_by_symbol_name_and_address() {
+        let mut _instance = mock_instantiate();
+
+        let token_info = new_token_info();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+
+        let expected = Entry {
+            id: 1,
+            name: "test token".to_string(),
+            symbol: "ttt".to_string(),
+            logo: Logo::Url("logo_url".to_string()),
+            contract_addr: Addr::unchecked("contract1"),
+            creator: Addr::unchecked(USER),
+            code_id: _instance.msg.token_code_id,
+            verified: false,
+            managed_upgrades: false,
+            migration: None,
+            pool_addr: None,
+        };
+
+        // lookups are case insensitive
+        let queries = vec![
+            QueryMsg::TokenBySymbol {
+                symbol: "TTT".to_string(),
+            },
+            QueryMsg::TokenByName {
+                name: "Test Token".to_string(),
+            },
+            QueryMsg::TokenByAddress {
+                contract_addr: "contract1".to_string(),
+            },
+        ];
+        for msg in queries {
+            let res: TokenResponse = _instance
+                .app
+                .wrap()
+                .query_wasm_smart(_instance.c_addr.clone(), &msg)
+                .unwrap();
+            assert_eq!(res.entry, Some(expected.clone()));
+        }
+
+        // unknown tokens return no entry
+        let queries = vec![
+            QueryMsg::TokenBySymbol {
+                symbol: "XYZ".to_string(),
+            },
+            QueryMsg::TokenByName {
+                name: "Unknown Token".to_string(),
+            },
+            QueryMsg::TokenByAddress {
+                contract_addr: "contract2".to_string(),
+            },
+        ];
+        for msg in queries {
+            let res: TokenResponse = _instance
+                .app
+                .wrap()
+                .query_wasm_smart(_instance.c_addr.clone(), &msg)
+                .unwrap();
+            assert_eq!(res.entry, None);
+        }
+    }
+
+    #[test]
+    fn test_query_tokens_by_creator() {
+        let mut _instance = mock_instantiate();
+
+        // USER creates three tokens and OTHER_USER creates one
+        let tokens = vec![
+            (USER, "CCC", "Token C"),
+            (OTHER_USER, "DDD", "Token D"),
+            (USER, "AAA", "Token A"),
+            (USER, "BBB", "Token B"),
+        ];
+        for (creator, symbol, name) in tokens {
+            let mut token_info = new_token_info();
+            token_info.symbol = symbol.to_string();
+            token_info.name = name.to_string();
+            _instance
+                .app
+                .execute_contract(
+                    Addr::unchecked(creator),
+                    _instance.c_addr.clone(),
+                    &ExecuteMsg::CreateToken {
+                        token_info,
+                        managed_upgrades: None,
+                    },
+                    &[_instance.msg.token_creation_fees[0].clone()],
+                )
+                .unwrap();
+        }
+
+        // the first page is ordered by symbol
+        let msg = QueryMsg::TokensByCreator {
+            creator: USER.to_string(),
+            start_after: None,
+            limit: Some(2),
+        };
+        let res: TokenListResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(_instance.c_addr.clone(), &msg)
+            .unwrap();
+        let symbols: Vec<String> = res.entries.iter().map(|e| e.symbol.clone()).collect();
+        assert_eq!(symbols, vec!["aaa", "bbb"]);
+        assert!(res.entries.iter().all(|e| e.creator == USER));
+
+        // the next page starts after the last symbol
+        let msg = QueryMsg::TokensByCreator {
+            creator: USER.to_string(),
+            start_after: Some("BBB".to_string()),
+            limit: Some(2),
+        };
+        let res: TokenListResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(_instance.c_addr.clone(), &msg)
+            .unwrap();
+        let symbols: Vec<String> = res.entries.iter().map(|e| e.symbol.clone()).collect();
+        assert_eq!(symbols, vec!["ccc"]);
+
+        let msg = QueryMsg::TokensByCreator {
+            creator: OTHER_USER.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: TokenListResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(_instance.c_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(res.entries.len(), 1);
+        assert_eq!(res.entries[0].symbol, "ddd");
+        assert_eq!(res.entries[0].creator, OTHER_USER);
+    }
+
+    #[test]
+    fn test_create_tokens_in_one_batch() {
+        let mut _instance = mock_instantiate();
+
+        // several CreateToken messages sent in the same transaction
+        let tokens = vec![("AAA", "Token A"), ("BBB", "Token B"), ("CCC", "Token C")];
+        let msgs: Vec<CosmosMsg> = tokens
+            .iter()
+            .map(|(symbol, name)| {
+                let mut token_info = new_token_info();
+                token_info.symbol = symbol.to_string();
+                token_info.name = name.to_string();
+                WasmMsg::Execute {
+                    contract_addr: _instance.c_addr.to_string(),
+                    msg: to_binary(&ExecuteMsg::CreateToken {
+                        token_info,
+                        managed_upgrades: None,
+                    })
+                    .unwrap(),
+                    funds: vec![_instance.msg.token_creation_fees[0].clone()],
+                }
+                .into()
+            })
+            .collect();
+        _instance
+            .app
+            .execute_multi(Addr::unchecked(USER), msgs)
+            .unwrap();
+
+        // every entry points to the cw20 contract that was created for it
+        let list = get_token_list(&mut _instance.app, _instance.c_addr.clone());
+        assert_eq!(list.entries.len(), tokens.len());
+        for (entry, (symbol, name)) in list.entries.iter().zip(tokens) {
+            assert_eq!(entry.symbol, symbol.to_lowercase());
+            assert_eq!(entry.name, name.to_lowercase());
+
+            let token_info: cw20::TokenInfoResponse = _instance
+                .app
+                .wrap()
+                .query_wasm_smart(
+                    entry.contract_addr.clone(),
+                    &cw20::Cw20QueryMsg::TokenInfo {},
+                )
+                .unwrap();
+            assert_eq!(token_info.symbol, symbol);
+            assert_eq!(token_info.name, name);
+        }
+    }
+
+    #[test]
+    fn test_create_new_token_routes_fee_and_refunds_overpayment() {
+        let mut _instance = mock_instantiate();
+
+        // split the fee between the burn, the treasury and the burn contract
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(ADMIN),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::UpdateFeeConfig {
+                    token_creation_fees: None,
+                    fee_split: Some(FeeSplit {
+                        burn: Decimal::percent(50),
+                        treasury: Decimal::percent(30),
+                        burn_contract: Decimal::percent(20),
+                    }),
+                    treasury_addr: Some(AddrUpdate::Set {
+                        address: TREASURY.to_string(),
+                    }),
+                    burn_contract_addr: Some(AddrUpdate::Set {
+                        address: BURN_CONTRACT.to_string(),
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+
+        // pay twice the fee
+        let fee = _instance.msg.token_creation_fees[0].amount.u128();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info: new_token_info(),
+                    managed_upgrades: None,
+                },
+                &coins(fee * 2, NATIVE_DENOM),
+            )
+            .unwrap();
+
+        let balance = |app: &App, addr: &str| {
+            app.wrap()
+                .query_balance(addr, NATIVE_DENOM)
+                .unwrap()
+                .amount
+                .u128()
+        };
+        assert_eq!(balance(&_instance.app, USER), SUPPLY - fee);
+        assert_eq!(balance(&_instance.app, TREASURY), fee * 3 / 10);
+        assert_eq!(balance(&_instance.app, BURN_CONTRACT), fee * 2 / 10);
+        assert_eq!(balance(&_instance.app, _instance.c_addr.as_str()), 0);
+    }
+
+    #[test]
+    fn test_update_code_id_records_code_id_on_entries() {
+        let mut _instance = mock_instantiate();
+
+        let token_info = new_token_info();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+
+        // governance moves the manager to a newer cw20 code id
+        let new_code_id = _instance.app.store_code(contract_cw20());
+        _instance
+            .app
+            .wasm_sudo(
+                _instance.c_addr.clone(),
+                &SudoMsg::UpdateCodeId {
+                    token_code_id: new_code_id,
+                },
+            )
+            .unwrap();
+
+        let mut token_info = new_token_info();
+        token_info.symbol = "NEW".to_string();
+        token_info.name = "New Token".to_string();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+
+        // every entry keeps the code id it was created with
+        let list = get_token_list(&mut _instance.app, _instance.c_addr.clone());
+        assert_eq!(list.entries[0].code_id, _instance.msg.token_code_id);
+        assert_eq!(list.entries[1].code_id, new_code_id);
+
+        let contract_info = _instance
+            .app
+            .wrap()
+            .query_wasm_contract_info(list.entries[1].contract_addr.clone())
+            .unwrap();
+        assert_eq!(contract_info.code_id, new_code_id);
+    }
+
+    fn create_token(
+        instance: &mut InstantiationResponse,
+        symbol: &str,
+        name: &str,
+        managed_upgrades: bool,
+    ) -> Entry {
+        let mut token_info = new_token_info();
+        token_info.symbol = symbol.to_string();
+        token_info.name = name.to_string();
+        instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: Some(managed_upgrades),
+                },
+                &[instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+        get_token(instance, symbol)
+    }
+
+    fn get_token(instance: &InstantiationResponse, symbol: &str) -> Entry {
+        let res: TokenResponse = instance
+            .app
+            .wrap()
+            .query_wasm_smart(
+                instance.c_addr.clone(),
+                &QueryMsg::TokenBySymbol {
+                    symbol: symbol.to_string(),
+                },
+            )
+            .unwrap();
+        res.entry.unwrap()
+    }
+
+    #[test]
+    fn test_update_entry_refreshes_logo() {
+        let mut _instance = mock_instantiate();
+        let entry = create_token(&mut _instance, "TTT", "Test Token", false);
+        let verify = ExecuteMsg::SetVerified {
+            symbol: "TTT".to_string(),
+            verified: true,
+        };
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(CURATOR),
+                _instance.c_addr.clone(),
+                &verify,
+                &[],
+            )
+            .unwrap();
+
+        // the marketing address changes the logo on the cw20 directly
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MARKETING),
+                entry.contract_addr.clone(),
+                &cw20::Cw20ExecuteMsg::UploadLogo(Logo::Url("new_logo_url".to_string())),
+                &[],
+            )
+            .unwrap();
+
+        // only the creator or the marketing address can refresh the entry
+        let msg = ExecuteMsg::UpdateEntry {
+            symbol: "TTT".to_string(),
+        };
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MARKETING),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        let entry = get_token(&_instance, "TTT");
+        assert_eq!(entry.logo, Logo::Url("new_logo_url".to_string()));
+
+        // the curator has to verify the new logo again
+        assert!(!entry.verified);
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(CURATOR),
+                _instance.c_addr.clone(),
+                &verify,
+                &[],
+            )
+            .unwrap();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MARKETING),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert!(get_token(&_instance, "TTT").verified);
+
+        // an embedded logo is downloaded from the token
+        let svg = Binary::from(b"<?xml version=\"1.0\"?><svg></svg>".to_vec());
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MARKETING),
+                entry.contract_addr.clone(),
+                &cw20::Cw20ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(svg.clone()))),
+                &[],
+            )
+            .unwrap();
+        _instance
+            .app
+            .execute_contract(Addr::unchecked(USER), _instance.c_addr.clone(), &msg, &[])
+            .unwrap();
+        let entry = get_token(&_instance, "TTT");
+        assert_eq!(entry.logo, Logo::Embedded(EmbeddedLogo::Svg(svg)));
+    }
+
+    #[test]
+    fn test_update_entry_rejects_changed_symbol() {
+        let mut _instance = mock_instantiate();
+        create_token(&mut _instance, "TTT", "Test Token", true);
+
+        let renamed_code_id = _instance.app.store_code(contract_cw20_renamed());
+        _instance
+            .app
+            .wasm_sudo(
+                _instance.c_addr.clone(),
+                &SudoMsg::MigrateTokens {
+                    code_id: Some(renamed_code_id),
+                    migrate_msg: to_binary(&cw20_base::msg::MigrateMsg {}).unwrap(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::UpdateEntry {
+                    symbol: "TTT".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::SymbolChanged {
+                symbol: "ttt".to_string(),
+                new_symbol: "OTHER".to_string(),
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_set_verified_and_filter_token_list() {
+        let mut _instance = mock_instantiate();
+        create_token(&mut _instance, "AAA", "Token A", false);
+        create_token(&mut _instance, "BBB", "Token B", false);
+        create_token(&mut _instance, "CCC", "Token C", false);
+
+        // only the curator can verify a token
+        let msg = ExecuteMsg::SetVerified {
+            symbol: "BBB".to_string(),
+            verified: true,
+        };
+        let _err = _instance
+            .app
+            .execute_contract(Addr::unchecked(USER), _instance.c_addr.clone(), &msg, &[])
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(CURATOR),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert!(get_token(&_instance, "BBB").verified);
+
+        let query_list = |verified: Option<bool>| -> Vec<String> {
+            let msg = QueryMsg::QueryTokenList {
+                start_after: None,
+                limit: None,
+                verified,
+            };
+            let res: TokenListResponse = _instance
+                .app
+                .wrap()
+                .query_wasm_smart(_instance.c_addr.clone(), &msg)
+                .unwrap();
+            res.entries.into_iter().map(|e| e.symbol).collect()
+        };
+        assert_eq!(query_list(Some(true)), vec!["bbb"]);
+        assert_eq!(query_list(Some(false)), vec!["aaa", "ccc"]);
+        assert_eq!(query_list(None), vec!["aaa", "bbb", "ccc"]);
+    }
+
+    fn get_code_id(instance: &InstantiationResponse, contract_addr: &Addr) -> u64 {
+        instance
+            .app
+            .wrap()
+            .query_wasm_contract_info(contract_addr)
+            .unwrap()
+            .code_id
+    }
+
+    #[test]
+    fn test_migrate_managed_tokens() {
+        let mut _instance = mock_instantiate();
+        let token_a = create_token(&mut _instance, "AAA", "Token A", true);
+        let token_b = create_token(&mut _instance, "BBB", "Token B", false);
+        let token_c = create_token(&mut _instance, "CCC", "Token C", true);
+
+        // the manager is the wasm admin of the managed tokens only
+        let admin =
+            |app: &App, addr: &Addr| app.wrap().query_wasm_contract_info(addr).unwrap().admin;
+        assert_eq!(
+            admin(&_instance.app, &token_a.contract_addr),
+            Some(_instance.c_addr.to_string())
+        );
+        assert_eq!(
+            admin(&_instance.app, &token_b.contract_addr),
+            Some(USER.to_string())
+        );
+
+        // migrate the first page of one managed token
+        let new_code_id = _instance.app.store_code(contract_cw20());
+        let migrate_msg = to_binary(&cw20_base::msg::MigrateMsg {}).unwrap();
+        let res = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(ADMIN),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::MigrateTokens {
+                    code_id: Some(new_code_id),
+                    migrate_msg: migrate_msg.clone(),
+                    start_after: None,
+                    limit: Some(1),
+                },
+                &[],
+            )
+            .unwrap();
+        assert!(res.has_event(&Event::new("wasm").add_attribute("last_symbol", "aaa")));
+        assert_eq!(get_code_id(&_instance, &token_a.contract_addr), new_code_id);
+        assert_eq!(
+            get_code_id(&_instance, &token_c.contract_addr),
+            _instance.msg.token_code_id
+        );
+        let entry = get_token(&_instance, "AAA");
+        assert_eq!(entry.code_id, new_code_id);
+        assert_eq!(
+            entry.migration,
+            Some(MigrationStatus::Migrated {
+                code_id: new_code_id
+            })
+        );
+
+        // the next page skips the unmanaged token
+        _instance
+            .app
+            .wasm_sudo(
+                _instance.c_addr.clone(),
+                &SudoMsg::MigrateTokens {
+                    code_id: Some(new_code_id),
+                    migrate_msg: migrate_msg.clone(),
+                    start_after: Some("AAA".to_string()),
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(get_code_id(&_instance, &token_c.contract_addr), new_code_id);
+        assert_eq!(
+            get_code_id(&_instance, &token_b.contract_addr),
+            _instance.msg.token_code_id
+        );
+        assert_eq!(get_token(&_instance, "BBB").migration, None);
+
+        // a failing migration is recorded without reverting the batch
+        let broken_code_id = _instance.app.store_code(contract_template());
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(ADMIN),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::MigrateTokens {
+                    code_id: Some(broken_code_id),
+                    migrate_msg,
+                    start_after: None,
+                    limit: None,
+                },
+                &[],
+            )
+            .unwrap();
+        for (symbol, token) in [("AAA", &token_a), ("CCC", &token_c)] {
+            assert_eq!(get_code_id(&_instance, &token.contract_addr), new_code_id);
+            let entry = get_token(&_instance, symbol);
+            assert_eq!(entry.code_id, new_code_id);
+            match entry.migration {
+                Some(MigrationStatus::Failed { code_id, .. }) => {
+                    assert_eq!(code_id, broken_code_id)
+                }
+                status => panic!("unexpected migration status: {:?}", status),
+            }
+        }
+
+        // the failed tokens are skipped by the next batches to the same code id
+        let res = _instance
+            .app
+            .wasm_sudo(
+                _instance.c_addr.clone(),
+                &SudoMsg::MigrateTokens {
+                    code_id: Some(broken_code_id),
+                    migrate_msg: to_binary(&cw20_base::msg::MigrateMsg {}).unwrap(),
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        let wasm = res.events.iter().find(|e| e.ty == "wasm").unwrap();
+        let attribute = |key: &str| {
+            wasm.attributes
+                .iter()
+                .find(|a| a.key == key)
+                .map(|a| a.value.clone())
+        };
+        assert_eq!(attribute("count"), Some("0".to_string()));
+        assert_eq!(attribute("last_symbol"), None);
+    }
+
+    #[test]
+    fn test_create_new_token_with_initial_pool() {
+        let mut _instance = mock_instantiate();
+
+        let base_amount = 1_000_000u128;
+        let quote_amount = 5_000_000u128;
+        let mut token_info = new_token_info();
+        token_info.initial_pool = Some(InitialPool {
+            base_amount: Uint128::from(base_amount),
+            quote_amount: Uint128::from(quote_amount),
+        });
+
+        // the base liquidity is sent on top of the fee
+        let fee = _instance.msg.token_creation_fees[0].amount.u128();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &coins(fee + base_amount, NATIVE_DENOM),
+            )
+            .unwrap();
+
+        // the pool is recorded on the entry and holds the initial liquidity
+        let entry = get_token(&_instance, "TTT");
+        let pool_addr = entry.pool_addr.unwrap();
+        let info: token_swap::msg::InfoResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(pool_addr, &token_swap::msg::QueryMsg::Info {})
+            .unwrap();
+        assert_eq!(info.base_reserve, Uint128::from(base_amount));
+        assert_eq!(info.quote_reserve, Uint128::from(quote_amount));
+        assert_eq!(
+            info.quote_denom,
+            cw20::Denom::Cw20(entry.contract_addr.clone())
+        );
+
+        // the creator gets the LP tokens and the manager keeps nothing
+        let balance = |token: &Addr, address: &Addr| -> Uint128 {
+            let res: cw20::BalanceResponse = _instance
+                .app
+                .wrap()
+                .query_wasm_smart(
+                    token,
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.balance
+        };
+        assert_eq!(
+            balance(&info.lp_token_address, &Addr::unchecked(USER)),
+            info.lp_token_supply
+        );
+        assert!(!info.lp_token_supply.is_zero());
+        assert_eq!(
+            balance(&info.lp_token_address, &_instance.c_addr),
+            Uint128::zero()
+        );
+        assert_eq!(
+            balance(&entry.contract_addr, &_instance.c_addr),
+            Uint128::zero()
+        );
+        assert_eq!(
+            _instance
+                .app
+                .wrap()
+                .query_balance(_instance.c_addr.clone(), NATIVE_DENOM)
+                .unwrap()
+                .amount,
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn test_vesting_and_claim() {
+        let mut _instance = mock_instantiate();
+
+        let amount = Uint128::from(1_000_000u128);
+        let mut token_info = new_token_info();
+        token_info.vesting = vec![VestingAllocation {
+            recipient: OTHER_USER.to_string(),
+            amount,
+            cliff_seconds: 100,
+            duration_seconds: 1000,
+        }];
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::CreateToken {
+                    token_info,
+                    managed_upgrades: None,
+                },
+                &[_instance.msg.token_creation_fees[0].clone()],
+            )
+            .unwrap();
+        let entry = get_token(&_instance, "TTT");
+        let c_addr = _instance.c_addr.clone();
+
+        let query_vesting = |app: &App| -> VestingResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    c_addr.clone(),
+                    &QueryMsg::Vesting {
+                        recipient: OTHER_USER.to_string(),
+                        symbol: "TTT".to_string(),
+                    },
+                )
+                .unwrap()
+        };
+        let token_balance = |app: &App, address: &str| -> Uint128 {
+            let res: cw20::BalanceResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    entry.contract_addr.clone(),
+                    &cw20::Cw20QueryMsg::Balance {
+                        address: address.to_string(),
+                    },
+                )
+                .unwrap();
+            res.balance
+        };
+
+        // the allocation is held by the manager
+        assert_eq!(
+            token_balance(&_instance.app, _instance.c_addr.as_str()),
+            amount
+        );
+        assert_eq!(token_balance(&_instance.app, OTHER_USER), Uint128::zero());
+
+        // nothing can be claimed before the cliff
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(99));
+        assert_eq!(query_vesting(&_instance.app).vested, Uint128::zero());
+        let msg = ExecuteMsg::Claim {
+            symbol: "TTT".to_string(),
+        };
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::NothingToClaim {}.to_string()
+        );
+
+        // halfway through the duration half of the allocation is vested
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(401));
+        let vesting = query_vesting(&_instance.app);
+        assert_eq!(vesting.claimable, Uint128::from(500_000u128));
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(
+            token_balance(&_instance.app, OTHER_USER),
+            Uint128::from(500_000u128)
+        );
+        assert_eq!(query_vesting(&_instance.app).claimable, Uint128::zero());
+
+        // everything is released at the end of the duration
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(1000));
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &msg,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(token_balance(&_instance.app, OTHER_USER), amount);
+        assert_eq!(
+            token_balance(&_instance.app, _instance.c_addr.as_str()),
+            Uint128::zero()
+        );
+
+        let res: VestingsResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(
+                _instance.c_addr.clone(),
+                &QueryMsg::VestingsByRecipient {
+                    recipient: OTHER_USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.vestings.len(), 1);
+        assert_eq!(res.vestings[0].vesting.claimed, amount);
+        assert_eq!(res.vestings[0].vesting.token, entry.contract_addr);
+    }
 }
@@ -1,20 +1,135 @@
-use cosmwasm_std::{Coin, StdError, StdResult, Uint128};
+use cosmwasm_std::{Binary, Coin, Uint128};
 use cw20::{Cw20Coin, Logo, MinterResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use unicode_normalization::{is_nfkc, UnicodeNormalization};
+use unicode_security::{skeleton, GeneralSecurityProfile, MixedScript};
 
-use crate::state::Entry;
+use crate::state::{Entry, FeeSplit, PoolCodeIds, Vesting};
+use crate::ContractError;
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
-    pub token_creation_fee: Coin,
+    pub token_creation_fees: Vec<Coin>,
     pub token_code_id: u64,
+    // defaults to the instantiator
+    pub admin: Option<String>,
+    // defaults to burning the whole fee
+    pub fee_split: Option<FeeSplit>,
+    pub treasury_addr: Option<String>,
+    pub burn_contract_addr: Option<String>,
+    // defaults to the admin
+    pub curator: Option<String>,
+    // symbols no one can create a token with, e.g. HUAHUA, ATOM or USDC
+    pub reserved_symbols: Option<Vec<String>>,
+    // defaults to no initial pools
+    pub pool_code_ids: Option<PoolCodeIds>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
-    CreateToken { token_info: TokenInfo },
+    CreateToken {
+        token_info: TokenInfo,
+        // make the manager the wasm admin of the token so it can be migrated
+        // by MigrateTokens, defaults to false and the creator as admin
+        managed_upgrades: Option<bool>,
+    },
+    // Creator only, hand the wasm admin of a managed token back to the creator
+    OptOutOfUpgrades {
+        symbol: String,
+    },
+    // Send the vested tokens of the caller for the token with symbol
+    Claim {
+        symbol: String,
+    },
+    // Creator or cw20 marketing address only, refresh the entry name and logo
+    // from the token contract
+    UpdateEntry {
+        symbol: String,
+    },
+    // Curator only
+    SetVerified {
+        symbol: String,
+        verified: bool,
+    },
+
+    // Admin only, fields left as None are not changed
+    UpdateFeeConfig {
+        token_creation_fees: Option<Vec<Coin>>,
+        fee_split: Option<FeeSplit>,
+        treasury_addr: Option<AddrUpdate>,
+        burn_contract_addr: Option<AddrUpdate>,
+    },
+    UpdateCodeId {
+        token_code_id: u64,
+    },
+    UpdatePoolCodeIds {
+        pool_code_ids: Option<PoolCodeIds>,
+    },
+    UpdateAdmin {
+        admin: String,
+    },
+    UpdateCurator {
+        curator: String,
+    },
+    UpdateReservedSymbols {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    // Migrate the managed tokens that are not on code_id yet (defaults to
+    // token_code_id), paginated by symbol
+    MigrateTokens {
+        code_id: Option<u64>,
+        migrate_msg: Binary,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    UpdateFeeConfig {
+        token_creation_fees: Option<Vec<Coin>>,
+        fee_split: Option<FeeSplit>,
+        treasury_addr: Option<AddrUpdate>,
+        burn_contract_addr: Option<AddrUpdate>,
+    },
+    UpdateCodeId {
+        token_code_id: u64,
+    },
+    UpdatePoolCodeIds {
+        pool_code_ids: Option<PoolCodeIds>,
+    },
+    UpdateAdmin {
+        admin: String,
+    },
+    UpdateCurator {
+        curator: String,
+    },
+    UpdateReservedSymbols {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    // Migrate the managed tokens that are not on code_id yet (defaults to
+    // token_code_id), paginated by symbol
+    MigrateTokens {
+        code_id: Option<u64>,
+        migrate_msg: Binary,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+// Set or clear one of the fee destination addresses of the config, an address
+// can only be cleared while its fee_split share is zero
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AddrUpdate {
+    Set { address: String },
+    Clear {},
 }
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -33,6 +148,33 @@ pub struct TokenInfo {
     pub initial_balances: Vec<Cw20Coin>,
     pub mint: Option<MinterResponse>,
     pub marketing: MarketingInfo,
+    // Not part of the cw20 instantiate message, left out when None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_pool: Option<InitialPool>,
+    // Not part of the cw20 instantiate message, left out when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vesting: Vec<VestingAllocation>,
+}
+
+// Tokens minted to the manager and released to recipient from the creation
+// of the token: nothing before cliff_seconds, then linearly until
+// duration_seconds when the whole amount can be claimed
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct VestingAllocation {
+    pub recipient: String,
+    pub amount: Uint128,
+    pub cliff_seconds: u64,
+    pub duration_seconds: u64,
+}
+
+// A token-swap pool between the base_denom of the pool_code_ids of the config
+// and the new token (quote) created right after the token. The manager mints
+// quote_amount of the new token to itself and the caller sends base_amount of
+// base_denom on top of the token_creation_fee, the LP tokens go to the creator
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
+pub struct InitialPool {
+    pub base_amount: Uint128,
+    pub quote_amount: Uint128,
 }
 
 impl TokenInfo {
@@ -40,44 +182,97 @@ impl TokenInfo {
         self.mint.as_ref().and_then(|v| v.cap)
     }
 
-    pub fn validate(&self) -> StdResult<()> {
+    pub fn validate(&self) -> Result<(), ContractError> {
         // Check name, symbol, decimals
-        if !is_valid_name(&self.name) {
-            return Err(StdError::generic_err(
-                "Name is not in the expected format (3-50 UTF-8 bytes)",
-            ));
-        }
-        if !is_valid_symbol(&self.symbol) {
-            return Err(StdError::generic_err(
-                "Ticker symbol is not in expected format [a-zA-Z\\-]{3,12}",
-            ));
-        }
-        if self.decimals > 18 {
-            return Err(StdError::generic_err("Decimals must not exceed 18"));
+        validate_name(&self.name)?;
+        validate_symbol(&self.symbol)?;
+        if self.decimals > MAX_DECIMALS {
+            return Err(ContractError::InvalidDecimals { max: MAX_DECIMALS });
         }
         Ok(())
     }
 }
 
-fn is_valid_name(name: &str) -> bool {
-    let bytes = name.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 50 {
-        return false;
+const MIN_NAME_LENGTH: usize = 3;
+const MAX_NAME_LENGTH: usize = 50;
+const MIN_SYMBOL_LENGTH: usize = 3;
+const MAX_SYMBOL_LENGTH: usize = 12;
+const MAX_DECIMALS: u8 = 18;
+
+/**
+ * Names are created in NFKC form, so compatibility variants such as full
+ * width letters or ligatures are folded into the characters they stand for
+ */
+pub fn normalize_name(name: &str) -> String {
+    name.nfkc().collect()
+}
+
+/**
+ * Names must be in NFKC form and only use printable ASCII or characters
+ * allowed in identifiers by UTS #39, which leaves out invisible and format
+ * characters. Letters can't mix scripts, so a Cyrillic а can't stand in for
+ * a Latin a. Spaces are only allowed between words, one at a time
+ */
+pub(crate) fn validate_name(name: &str) -> Result<(), ContractError> {
+    if name.len() < MIN_NAME_LENGTH || name.len() > MAX_NAME_LENGTH {
+        return Err(ContractError::InvalidNameLength {
+            min: MIN_NAME_LENGTH,
+            max: MAX_NAME_LENGTH,
+        });
+    }
+    if !is_nfkc(name) {
+        return Err(ContractError::NonNormalizedName {});
+    }
+    for character in name.chars() {
+        if character.is_control() {
+            return Err(ContractError::ControlCharacterInName { character });
+        }
+        if character != ' ' && !character.is_ascii_graphic() && !character.identifier_allowed() {
+            return Err(ContractError::DisallowedCharacterInName { character });
+        }
     }
-    true
+    if !name.is_single_script() {
+        return Err(ContractError::MixedScriptName {});
+    }
+    if name.starts_with(' ') || name.ends_with(' ') || name.contains("  ") {
+        return Err(ContractError::InvalidNameSpacing {});
+    }
+    Ok(())
 }
 
-fn is_valid_symbol(symbol: &str) -> bool {
-    let bytes = symbol.as_bytes();
-    if bytes.len() < 3 || bytes.len() > 12 {
-        return false;
+// Same charset as the is_valid_symbol of cw20-base [a-zA-Z\-]{3,12}
+pub(crate) fn validate_symbol(symbol: &str) -> Result<(), ContractError> {
+    if symbol.len() < MIN_SYMBOL_LENGTH || symbol.len() > MAX_SYMBOL_LENGTH {
+        return Err(ContractError::InvalidSymbolLength {
+            min: MIN_SYMBOL_LENGTH,
+            max: MAX_SYMBOL_LENGTH,
+        });
     }
-    for byte in bytes.iter() {
-        if (*byte != 45) && (*byte < 65 || *byte > 90) && (*byte < 97 || *byte > 122) {
-            return false;
+    for character in symbol.chars() {
+        if !character.is_ascii_alphabetic() && character != '-' {
+            return Err(ContractError::InvalidSymbolCharacter { character });
         }
     }
-    true
+    Ok(())
+}
+
+/**
+ * Reduce a symbol or name to a skeleton where characters that are easily
+ * mistaken for one another are the same, two tokens with the same skeleton
+ * can't coexist. Case is ignored, look-alikes are mapped with the UTS #39
+ * confusables, then accents, separators and spaces are dropped. 0 reads
+ * as o while 1 and i read as l
+ */
+pub fn confusable_skeleton(value: &str) -> String {
+    skeleton(&value.to_lowercase())
+        .flat_map(char::to_lowercase)
+        .filter(|c| c.is_alphanumeric())
+        .map(|c| match c {
+            '0' => 'o',
+            '1' | 'i' => 'l',
+            c => c,
+        })
+        .collect()
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -87,6 +282,37 @@ pub enum QueryMsg {
     QueryTokenList {
         start_after: Option<u64>,
         limit: Option<u32>,
+        // only list entries whose verified flag matches
+        verified: Option<bool>,
+    },
+    TokenBySymbol {
+        symbol: String,
+    },
+    TokenByName {
+        name: String,
+    },
+    TokenByAddress {
+        contract_addr: String,
+    },
+    TokensByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    // Paginated by symbol
+    ReservedSymbols {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    Vesting {
+        recipient: String,
+        symbol: String,
+    },
+    // Paginated by symbol
+    VestingsByRecipient {
+        recipient: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
     },
 }
 
@@ -94,3 +320,26 @@ pub enum QueryMsg {
 pub struct TokenListResponse {
     pub entries: Vec<Entry>,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenResponse {
+    pub entry: Option<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingResponse {
+    pub vesting: Vesting,
+    // vested so far, claimed or not
+    pub vested: Uint128,
+    pub claimable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingsResponse {
+    pub vestings: Vec<VestingResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReservedSymbolsResponse {
+    pub symbols: Vec<String>,
+}
@@ -1,13 +1,53 @@
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Decimal, Timestamp, Uint128};
+
+use crate::msg::{confusable_skeleton, InitialPool, VestingAllocation};
 use cw20::Logo;
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, UniqueIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub token_creation_fee: Coin,
+    // One accepted fee per denom, the caller pays with any of them
+    pub token_creation_fees: Vec<Coin>,
     pub token_code_id: u64,
+    pub admin: Addr,
+    pub fee_split: FeeSplit,
+    pub treasury_addr: Option<Addr>,
+    pub burn_contract_addr: Option<Addr>,
+    // Can flag entries as verified
+    pub curator: Addr,
+    // Initial pools are only instantiated from these code ids against
+    // base_denom, and are disabled while they are not set
+    pub pool_code_ids: Option<PoolCodeIds>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolCodeIds {
+    pub swap_code_id: u64,
+    pub lp_token_code_id: u64,
+    // The native denom every initial pool is paired against
+    pub base_denom: String,
+}
+
+// Shares of the collected token_creation_fee, they must add up to 1.
+// Whatever is left after rounding down the treasury and burn_contract
+// shares is burned
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeSplit {
+    pub burn: Decimal,
+    pub treasury: Decimal,
+    pub burn_contract: Decimal,
+}
+
+impl Default for FeeSplit {
+    fn default() -> Self {
+        FeeSplit {
+            burn: Decimal::one(),
+            treasury: Decimal::zero(),
+            burn_contract: Decimal::zero(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -15,6 +55,11 @@ pub struct TempEntry {
     pub name: String,
     pub symbol: String,
     pub logo: Logo,
+    pub creator: Addr,
+    pub code_id: u64,
+    pub managed_upgrades: bool,
+    pub initial_pool: Option<InitialPool>,
+    pub vesting: Vec<VestingAllocation>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -24,6 +69,61 @@ pub struct Entry {
     pub symbol: String,
     pub logo: Logo,
     pub contract_addr: Addr,
+    pub creator: Addr,
+    // The code id the cw20 contract was instantiated from
+    pub code_id: u64,
+    // Set by the curator
+    pub verified: bool,
+    // The manager is the wasm admin of the token and can migrate it
+    pub managed_upgrades: bool,
+    // Outcome of the last migration issued by the manager
+    pub migration: Option<MigrationStatus>,
+    // The token-swap pool created with the token
+    pub pool_addr: Option<Addr>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    Migrated { code_id: u64 },
+    Failed { code_id: u64, error: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vesting {
+    pub symbol: String,
+    pub token: Addr,
+    pub amount: Uint128,
+    pub claimed: Uint128,
+    pub start_time: Timestamp,
+    pub cliff_time: Timestamp,
+    pub end_time: Timestamp,
+}
+
+impl Vesting {
+    // Amount released at time, claimed or not
+    pub fn vested(&self, time: Timestamp) -> Uint128 {
+        if time < self.cliff_time {
+            return Uint128::zero();
+        }
+        if time >= self.end_time {
+            return self.amount;
+        }
+        let elapsed = time.seconds() - self.start_time.seconds();
+        let duration = self.end_time.seconds() - self.start_time.seconds();
+        self.amount.multiply_ratio(elapsed, duration)
+    }
+
+    pub fn claimable(&self, time: Timestamp) -> Uint128 {
+        self.vested(time) - self.claimed
+    }
+}
+
+// A migration waiting for its reply, the token symbol and the target code id
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingMigration {
+    pub symbol: String,
+    pub code_id: u64,
 }
 
 // Here we create a unique sub-index that maps the id to entry.
@@ -32,12 +132,26 @@ pub struct Entry {
 pub struct EntryIndexes<'a> {
     pub id: UniqueIndex<'a, u64, Entry>,
     pub name: UniqueIndex<'a, String, Entry>,
+    // Symbols and names reduced by confusable_skeleton must be unique too
+    pub symbol_skeleton: UniqueIndex<'a, String, Entry>,
+    pub name_skeleton: UniqueIndex<'a, String, Entry>,
+    pub contract_addr: UniqueIndex<'a, Addr, Entry>,
+    // Non unique index that maps a creator to all the tokens they created,
+    // the primary key (symbol) is used to paginate within a creator
+    pub creator: MultiIndex<'a, Addr, Entry, String>,
 }
 
 // This implements the get_indexes trait that returns the list od indexes
 impl IndexList<Entry> for EntryIndexes<'_> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Entry>> + '_> {
-        let v: Vec<&dyn Index<Entry>> = vec![&self.id, &self.name];
+        let v: Vec<&dyn Index<Entry>> = vec![
+            &self.id,
+            &self.name,
+            &self.symbol_skeleton,
+            &self.name_skeleton,
+            &self.contract_addr,
+            &self.creator,
+        ];
         Box::new(v.into_iter())
     }
 }
@@ -47,6 +161,16 @@ pub fn entries<'a>() -> IndexedMap<'a, &'a str, Entry, EntryIndexes<'a>> {
     let indexes = EntryIndexes {
         id: UniqueIndex::new(|e| e.id, "ENTRIES_BY_ID"),
         name: UniqueIndex::new(|e| e.name.clone(), "ENTRIES_BY_NAME"),
+        symbol_skeleton: UniqueIndex::new(
+            |e| confusable_skeleton(&e.symbol),
+            "ENTRIES_BY_SYMBOL_SKELETON",
+        ),
+        name_skeleton: UniqueIndex::new(
+            |e| confusable_skeleton(&e.name),
+            "ENTRIES_BY_NAME_SKELETON",
+        ),
+        contract_addr: UniqueIndex::new(|e| e.contract_addr.clone(), "ENTRIES_BY_ADDR"),
+        creator: MultiIndex::new(|e| e.creator.clone(), "ENTRY_LIST", "ENTRIES_BY_CREATOR"),
     };
 
     IndexedMap::new("ENTRY_LIST", indexes)
@@ -55,9 +179,32 @@ pub fn entries<'a>() -> IndexedMap<'a, &'a str, Entry, EntryIndexes<'a>> {
 // This stores the config variables during initialization of the contract
 pub const INIT_CONFIG: Item<Config> = Item::new("INIT_CONFIG");
 
-// This stores tbe TempEntry when creating a new token before the contract
-// address is gotten
-pub const TEMP_ENTRY_STATE: Item<TempEntry> = Item::new("TempEntry");
+// This stores the TempEntry of every token being created until the contract
+// address is gotten, keyed by the reply id of its instantiate submessage so
+// several creations in the same transaction can't overwrite each other
+pub const PENDING_ENTRIES: Map<u64, TempEntry> = Map::new("PENDING_ENTRIES");
+
+// This stores the migrations issued by MigrateTokens until their reply,
+// keyed by the reply id of the migrate submessage
+pub const PENDING_MIGRATIONS: Map<u64, PendingMigration> = Map::new("PENDING_MIGRATIONS");
+
+// This stores the initial pools being instantiated, keyed by the reply id
+// of the pool instantiate submessage, with the symbol of their token
+pub const PENDING_POOLS: Map<u64, (String, InitialPool)> = Map::new("PENDING_POOLS");
+
+// This stores the symbol of the tokens whose initial liquidity is being
+// added, keyed by the reply id of the AddLiquidity submessage
+pub const PENDING_LIQUIDITY: Map<u64, String> = Map::new("PENDING_LIQUIDITY");
+
+// This stores the vesting of every recipient keyed by (recipient, symbol),
+// the manager holds the vesting tokens until they are claimed
+pub const VESTINGS: Map<(&Addr, &str), Vesting> = Map::new("VESTINGS");
+
+// This stores the reserved symbols keyed by their confusable_skeleton
+pub const RESERVED_SYMBOLS: Map<&str, String> = Map::new("RESERVED_SYMBOLS");
+
+// This keeps track of the last reply id handed out to a pending creation
+pub const REPLY_SEQ: Item<u64> = Item::new("REPLY_SEQ");
 
 // This keeps track of the number of items in the ENTRY_LIST
 pub const ENTRY_SEQ: Item<u64> = Item::new("ENTRY_SEQ");
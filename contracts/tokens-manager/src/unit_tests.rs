@@ -2,15 +2,19 @@
 mod tests {
     use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
     use cosmwasm_std::{
-        from_binary, to_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Empty, Env,
-        MemoryStorage, OwnedDeps, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult, Uint128,
-        WasmMsg,
+        coins, from_binary, to_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, Empty,
+        Env, MemoryStorage, OwnedDeps, Reply, ReplyOn, SubMsg, SubMsgResponse, SubMsgResult,
+        Uint128, WasmMsg,
     };
     use cw20::Logo;
 
-    use crate::contract::{execute, instantiate, query, reply};
-    use crate::msg::{ExecuteMsg, InstantiateMsg, MarketingInfo, QueryMsg, TokenInfo};
-    use crate::state::{entries, Entry, TempEntry, TEMP_ENTRY_STATE};
+    use crate::contract::{execute, instantiate, query, reply, sudo};
+    use crate::msg::validate_name;
+    use crate::msg::{
+        AddrUpdate, ExecuteMsg, InitialPool, InstantiateMsg, MarketingInfo, QueryMsg,
+        ReservedSymbolsResponse, SudoMsg, TokenInfo, VestingAllocation,
+    };
+    use crate::state::{entries, Config, Entry, FeeSplit, PoolCodeIds, TempEntry, PENDING_ENTRIES};
     use crate::ContractError;
 
     struct InstantiationResponse {
@@ -28,10 +32,21 @@ mod tests {
 
         let msg = InstantiateMsg {
             token_code_id: 1234u64,
-            token_creation_fee: Coin {
+            token_creation_fees: vec![Coin {
                 amount: Uint128::from(100000000u128),
                 denom: "udenom".to_string(),
-            },
+            }],
+            admin: Some(caller.clone()),
+            fee_split: Some(FeeSplit::default()),
+            treasury_addr: None,
+            burn_contract_addr: None,
+            curator: Some(caller.clone()),
+            reserved_symbols: None,
+            pool_code_ids: Some(PoolCodeIds {
+                swap_code_id: 2u64,
+                lp_token_code_id: 1234u64,
+                base_denom: "udenom".to_string(),
+            }),
         };
 
         // we can just call .unwrap() to assert this was a success
@@ -66,6 +81,8 @@ mod tests {
                 marketing: "Test! Test! Test!".to_string(),
                 logo: Logo::Url("logo_url".to_string()),
             },
+            initial_pool: None,
+            vesting: vec![],
         }
     }
 
@@ -78,10 +95,11 @@ mod tests {
 
         let info = mock_info(
             &_instance.caller,
-            &[_instance.msg.token_creation_fee.clone()],
+            &[_instance.msg.token_creation_fees[0].clone()],
         );
         let msg = ExecuteMsg::CreateToken {
             token_info: token_info.clone(),
+            managed_upgrades: None,
         };
 
         // Here we call the execute function
@@ -99,7 +117,7 @@ mod tests {
         assert_eq!(
             _res.messages[0].msg,
             CosmosMsg::Bank(BankMsg::Burn {
-                amount: vec![_instance.msg.token_creation_fee]
+                amount: vec![_instance.msg.token_creation_fees[0].clone()]
             })
         );
         assert_eq!(
@@ -113,19 +131,24 @@ mod tests {
                     code_id: _instance.msg.token_code_id,
                     funds: vec![],
                     label: token_info.name.clone(),
-                    admin: Some(_instance.caller),
+                    admin: Some(_instance.caller.clone()),
                 }
                 .into()
             }
         );
 
-        // load the TEMP_ENTRY_STATE to see if it contains the right data
+        // load the PENDING_ENTRIES to see if it contains the right data
         assert_eq!(
-            TEMP_ENTRY_STATE.load(&_instance.deps.storage).unwrap(),
+            PENDING_ENTRIES.load(&_instance.deps.storage, 1).unwrap(),
             TempEntry {
                 name: token_info.name.to_lowercase(),
                 symbol: token_info.symbol.to_lowercase(),
                 logo: token_info.marketing.logo,
+                creator: Addr::unchecked(_instance.caller),
+                code_id: _instance.msg.token_code_id,
+                managed_upgrades: false,
+                initial_pool: None,
+                vesting: vec![],
             }
         );
     }
@@ -135,12 +158,13 @@ mod tests {
         let mut _instance = proper_initialization();
         let info = mock_info(
             &_instance.caller,
-            &[_instance.msg.token_creation_fee.clone()],
+            &[_instance.msg.token_creation_fees[0].clone()],
         );
         // create a new token
         let token_info = new_token_info();
         let msg = ExecuteMsg::CreateToken {
             token_info: token_info.clone(),
+            managed_upgrades: None,
         };
 
         // Here we call the execute function
@@ -183,8 +207,85 @@ mod tests {
                 name: token_info.name.to_lowercase(),
                 symbol: token_info.symbol.to_lowercase(),
                 logo: token_info.marketing.logo,
+                creator: Addr::unchecked(_instance.caller),
+                code_id: _instance.msg.token_code_id,
+                verified: false,
+                managed_upgrades: false,
+                migration: None,
+                pool_addr: None,
             }
         );
+
+        // the pending entry is cleaned up after the reply
+        assert!(!PENDING_ENTRIES.has(&_instance.deps.storage, 1));
+    }
+
+    #[test]
+    fn test_create_new_token_pending_entries_dont_collide() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        // start two creations before any reply is handled
+        let first_token = new_token_info();
+        let mut second_token = new_token_info();
+        second_token.symbol = "SSS".to_string();
+        second_token.name = "Second Token".to_string();
+
+        for (token_info, reply_id) in [(first_token.clone(), 1), (second_token.clone(), 2)] {
+            let msg = ExecuteMsg::CreateToken {
+                token_info,
+                managed_upgrades: None,
+            };
+            let _res = execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                info.clone(),
+                msg,
+            )
+            .unwrap();
+            assert_eq!(_res.messages[1].id, reply_id);
+        }
+
+        // the replies come back in reverse order
+        let replies = [
+            (2, "pair0002", &second_token),
+            (1, "pair0001", &first_token),
+        ];
+        for (reply_id, contract_addr, token_info) in replies {
+            let mut data = vec![10, contract_addr.len() as u8];
+            data.extend_from_slice(contract_addr.as_bytes());
+            let reply_msg = Reply {
+                id: reply_id,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: Some(data.into()),
+                }),
+            };
+            reply(_instance.deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+            let entry = entries()
+                .load(
+                    &_instance.deps.storage,
+                    token_info.symbol.to_lowercase().as_str(),
+                )
+                .unwrap();
+            assert_eq!(entry.contract_addr, Addr::unchecked(contract_addr));
+            assert_eq!(entry.name, token_info.name.to_lowercase());
+            assert!(!PENDING_ENTRIES.has(&_instance.deps.storage, reply_id));
+        }
+
+        // a reply id without a pending creation is rejected
+        let reply_msg = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        reply(_instance.deps.as_mut(), mock_env(), reply_msg).unwrap_err();
     }
 
     #[test]
@@ -192,13 +293,14 @@ mod tests {
         let mut _instance = proper_initialization();
         let info = mock_info(
             &_instance.caller,
-            &[_instance.msg.token_creation_fee.clone()],
+            &[_instance.msg.token_creation_fees[0].clone()],
         );
 
         // create a new token
         let token_info = new_token_info();
         let msg = ExecuteMsg::CreateToken {
             token_info: token_info.clone(),
+            managed_upgrades: None,
         };
         let _res = execute(
             _instance.deps.as_mut(),
@@ -220,7 +322,10 @@ mod tests {
 
         // when we try to create_new_token again with the same token_info, we get an error
         let token_info = new_token_info();
-        let msg = ExecuteMsg::CreateToken { token_info };
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
         let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
 
         // we expect the InsufficientContractBalance
@@ -235,13 +340,14 @@ mod tests {
         let mut _instance = proper_initialization();
         let info = mock_info(
             &_instance.caller,
-            &[_instance.msg.token_creation_fee.clone()],
+            &[_instance.msg.token_creation_fees[0].clone()],
         );
 
         // create a new token
         let token_info = new_token_info();
         let msg = ExecuteMsg::CreateToken {
             token_info: token_info.clone(),
+            managed_upgrades: None,
         };
         let _res = execute(
             _instance.deps.as_mut(),
@@ -265,7 +371,10 @@ mod tests {
         let mut token_info = new_token_info();
         token_info.symbol = "DFS".to_string();
 
-        let msg = ExecuteMsg::CreateToken { token_info };
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
         let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
 
         // we expect the InsufficientContractBalance
@@ -278,7 +387,7 @@ mod tests {
     #[test]
     fn test_create_new_token_with_wrong_fee() {
         let incorrect_fee = Coin {
-            amount: Uint128::from(200000000u128),
+            amount: Uint128::from(50000000u128),
             denom: "udenom".to_string(),
         };
 
@@ -289,6 +398,7 @@ mod tests {
         let token_info = new_token_info();
         let msg = ExecuteMsg::CreateToken {
             token_info: token_info.clone(),
+            managed_upgrades: None,
         };
         let _err = execute(
             _instance.deps.as_mut(),
@@ -304,4 +414,779 @@ mod tests {
             e => panic!("unexpected error: {}", e),
         }
     }
+
+    #[test]
+    fn test_create_new_token_refunds_overpayment() {
+        let mut _instance = proper_initialization();
+        let funds = vec![
+            Coin {
+                amount: Uint128::from(150000000u128),
+                denom: "udenom".to_string(),
+            },
+            Coin {
+                amount: Uint128::from(10u128),
+                denom: "uother".to_string(),
+            },
+        ];
+        let info = mock_info(&_instance.caller, &funds);
+
+        let msg = ExecuteMsg::CreateToken {
+            token_info: new_token_info(),
+            managed_upgrades: None,
+        };
+        let _res = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+
+        // the fee is burned and everything else is sent back
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![_instance.msg.token_creation_fees[0].clone()]
+            })
+        );
+        assert_eq!(
+            _res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: _instance.caller,
+                amount: vec![
+                    Coin {
+                        amount: Uint128::from(50000000u128),
+                        denom: "udenom".to_string(),
+                    },
+                    Coin {
+                        amount: Uint128::from(10u128),
+                        denom: "uother".to_string(),
+                    },
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_update_fee_config() {
+        let mut _instance = proper_initialization();
+
+        // only the admin can update the fee config
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: None,
+            fee_split: None,
+            treasury_addr: Some(AddrUpdate::Set {
+                address: "treasury".to_string(),
+            }),
+            burn_contract_addr: None,
+        };
+        let info = mock_info("anyone", &[]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            msg.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // shares must add up to 1
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: None,
+            fee_split: Some(FeeSplit {
+                burn: Decimal::percent(50),
+                treasury: Decimal::percent(40),
+                burn_contract: Decimal::zero(),
+            }),
+            treasury_addr: Some(AddrUpdate::Set {
+                address: "treasury".to_string(),
+            }),
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InvalidFeeSplit {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // a share can't go to the burn contract until its address is set
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: None,
+            fee_split: Some(FeeSplit {
+                burn: Decimal::percent(50),
+                treasury: Decimal::zero(),
+                burn_contract: Decimal::percent(50),
+            }),
+            treasury_addr: None,
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::MissingBurnContractAddress {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // fees must have a single coin per denom
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: Some(vec![
+                _instance.msg.token_creation_fees[0].clone(),
+                _instance.msg.token_creation_fees[0].clone(),
+            ]),
+            fee_split: None,
+            treasury_addr: None,
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InvalidTokenCreationFees {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // route 30% of the fee to the treasury and accept a second denom
+        let fees = vec![
+            _instance.msg.token_creation_fees[0].clone(),
+            Coin {
+                amount: Uint128::from(1000u128),
+                denom: "uother".to_string(),
+            },
+        ];
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: Some(fees.clone()),
+            fee_split: Some(FeeSplit {
+                burn: Decimal::percent(70),
+                treasury: Decimal::percent(30),
+                burn_contract: Decimal::zero(),
+            }),
+            treasury_addr: Some(AddrUpdate::Set {
+                address: "treasury".to_string(),
+            }),
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Config {},
+        )
+        .unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(config.token_creation_fees, fees);
+        assert_eq!(config.treasury_addr, Some(Addr::unchecked("treasury")));
+
+        // pay with the second denom
+        let info = mock_info(&_instance.caller, &[fees[1].clone()]);
+        let msg = ExecuteMsg::CreateToken {
+            token_info: new_token_info(),
+            managed_upgrades: None,
+        };
+        let _res = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![Coin {
+                    amount: Uint128::from(700u128),
+                    denom: "uother".to_string(),
+                }]
+            })
+        );
+        assert_eq!(
+            _res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: vec![Coin {
+                    amount: Uint128::from(300u128),
+                    denom: "uother".to_string(),
+                }]
+            })
+        );
+
+        // the treasury can't be cleared while it still gets a share
+        let clear_treasury = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: None,
+            fee_split: None,
+            treasury_addr: Some(AddrUpdate::Clear {}),
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            clear_treasury,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::MissingTreasuryAddress {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // but it can along with its share
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            token_creation_fees: None,
+            fee_split: Some(FeeSplit {
+                burn: Decimal::one(),
+                treasury: Decimal::zero(),
+                burn_contract: Decimal::zero(),
+            }),
+            treasury_addr: Some(AddrUpdate::Clear {}),
+            burn_contract_addr: None,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Config {},
+        )
+        .unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(config.treasury_addr, None);
+    }
+
+    #[test]
+    fn test_update_code_id_and_admin() {
+        let mut _instance = proper_initialization();
+
+        // only the admin can update the code id
+        let msg = ExecuteMsg::UpdateCodeId {
+            token_code_id: 4321u64,
+        };
+        let info = mock_info("anyone", &[]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            msg.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let info = mock_info(&_instance.caller, &[]);
+        execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+
+        // governance hands the admin role over
+        let msg = SudoMsg::UpdateAdmin {
+            admin: "new_admin".to_string(),
+        };
+        sudo(_instance.deps.as_mut(), _instance.env.clone(), msg).unwrap();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Config {},
+        )
+        .unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(config.token_code_id, 4321u64);
+        assert_eq!(config.admin, Addr::unchecked("new_admin"));
+
+        // the previous admin can't update the config anymore
+        let msg = ExecuteMsg::UpdateCodeId {
+            token_code_id: 1234u64,
+        };
+        let info = mock_info(&_instance.caller, &[]);
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_opt_out_of_upgrades() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        // create a token upgraded by the manager
+        let msg = ExecuteMsg::CreateToken {
+            token_info: new_token_info(),
+            managed_upgrades: Some(true),
+        };
+        let _res = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+        match &_res.messages[1].msg {
+            CosmosMsg::Wasm(WasmMsg::Instantiate { admin, .. }) => {
+                assert_eq!(admin, &Some(_instance.env.contract.address.to_string()))
+            }
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+
+        let reply_msg = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+            }),
+        };
+        reply(_instance.deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        // only the creator can opt out
+        let msg = ExecuteMsg::OptOutOfUpgrades {
+            symbol: "TTT".to_string(),
+        };
+        let info = mock_info("anyone", &[]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            msg.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the wasm admin goes back to the creator
+        let info = mock_info(&_instance.caller, &[]);
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::UpdateAdmin {
+                contract_addr: "pair0000".to_string(),
+                admin: _instance.caller.clone(),
+            })
+        );
+        let entry = entries().load(&_instance.deps.storage, "ttt").unwrap();
+        assert!(!entry.managed_upgrades);
+
+        // opting out twice fails
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::TokenNotManaged { symbol: _ } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the token is left out of the migrations
+        let msg = SudoMsg::MigrateTokens {
+            code_id: Some(4321u64),
+            migrate_msg: to_binary(&Empty {}).unwrap(),
+            start_after: None,
+            limit: None,
+        };
+        let _res = sudo(_instance.deps.as_mut(), _instance.env.clone(), msg).unwrap();
+        assert!(_res.messages.is_empty());
+    }
+
+    #[test]
+    fn test_create_new_token_with_insufficient_initial_liquidity() {
+        let mut _instance = proper_initialization();
+
+        // only the fee is sent, nothing is left for the pool
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+        let mut token_info = new_token_info();
+        token_info.initial_pool = Some(InitialPool {
+            base_amount: Uint128::from(1000u128),
+            quote_amount: Uint128::from(1000u128),
+        });
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::IncorrectTokenCreationFee {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the base_denom of the pool is not sent at all
+        let info = mock_info(
+            &_instance.caller,
+            &coins(100001000u128, "uother".to_string()),
+        );
+        let mut token_info = new_token_info();
+        token_info.initial_pool = Some(InitialPool {
+            base_amount: Uint128::from(1000u128),
+            quote_amount: Uint128::from(1000u128),
+        });
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InsufficientInitialLiquidity {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_create_new_token_with_initial_pool_disabled() {
+        let mut _instance = proper_initialization();
+
+        // governance disables the initial pools
+        let msg = SudoMsg::UpdatePoolCodeIds {
+            pool_code_ids: None,
+        };
+        sudo(_instance.deps.as_mut(), _instance.env.clone(), msg).unwrap();
+
+        let info = mock_info(
+            &_instance.caller,
+            &coins(100001000u128, "udenom".to_string()),
+        );
+        let mut token_info = new_token_info();
+        token_info.initial_pool = Some(InitialPool {
+            base_amount: Uint128::from(1000u128),
+            quote_amount: Uint128::from(1000u128),
+        });
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InitialPoolDisabled {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // only the admin can set them
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::UpdatePoolCodeIds {
+            pool_code_ids: Some(PoolCodeIds {
+                swap_code_id: 3u64,
+                lp_token_code_id: 1234u64,
+                base_denom: "udenom".to_string(),
+            }),
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_create_new_token_with_invalid_vesting() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        // the cliff can't be after the end of the vesting
+        let mut token_info = new_token_info();
+        token_info.vesting = vec![VestingAllocation {
+            recipient: "team".to_string(),
+            amount: Uint128::from(1000u128),
+            cliff_seconds: 200,
+            duration_seconds: 100,
+        }];
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidVesting {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // a recipient can't get two allocations of the same token
+        let allocation = VestingAllocation {
+            recipient: "team".to_string(),
+            amount: Uint128::from(1000u128),
+            cliff_seconds: 0,
+            duration_seconds: 100,
+        };
+        let mut token_info = new_token_info();
+        token_info.vesting = vec![allocation.clone(), allocation];
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::InvalidVesting {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_create_new_token_with_invalid_name_or_symbol() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        let cases = vec![
+            ("Tеst Token", "TTT", 6u8),
+            ("Test\u{200B}Token", "TTT", 6u8),
+            ("Test\tToken", "TTT", 6u8),
+            ("Test  Token", "TTT", 6u8),
+            (" Test Token", "TTT", 6u8),
+            ("Test Token", "T_T", 6u8),
+            ("Test Token", "T3ST", 6u8),
+            ("Test Token", "TT", 6u8),
+            ("Test Token", "TTT", 19u8),
+        ];
+        for (name, symbol, decimals) in cases {
+            let mut token_info = new_token_info();
+            token_info.name = name.to_string();
+            token_info.symbol = symbol.to_string();
+            token_info.decimals = decimals;
+            let msg = ExecuteMsg::CreateToken {
+                token_info,
+                managed_upgrades: None,
+            };
+            let _err = execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                info.clone(),
+                msg,
+            )
+            .unwrap_err();
+            match _err {
+                ContractError::MixedScriptName {}
+                | ContractError::DisallowedCharacterInName { .. }
+                | ContractError::ControlCharacterInName { .. }
+                | ContractError::InvalidNameSpacing {}
+                | ContractError::InvalidSymbolCharacter { .. }
+                | ContractError::InvalidSymbolLength { .. }
+                | ContractError::InvalidDecimals { .. } => {}
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+
+        // letters and dashes are accepted in symbols
+        let mut token_info = new_token_info();
+        token_info.symbol = "TE-ST".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+
+        // names are not limited to ASCII, and are created in NFKC form
+        let mut token_info = new_token_info();
+        token_info.name = "Ｔøken Ｃoin".to_string();
+        token_info.symbol = "TOKEN".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+        let entry = PENDING_ENTRIES.load(&_instance.deps.storage, 2).unwrap();
+        assert_eq!(entry.name, "tøken coin");
+
+        // a name that isn't normalized is rejected when it is refreshed from the token
+        match validate_name("ﬁne Token").unwrap_err() {
+            ContractError::NonNormalizedName {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_create_new_token_reserved_symbol() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        // only the admin can update the reserved symbols
+        let msg = ExecuteMsg::UpdateReservedSymbols {
+            add: vec!["HUAHUA".to_string()],
+            remove: vec![],
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("anyone", &[]),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let msg = SudoMsg::UpdateReservedSymbols {
+            add: vec!["HUAHUA".to_string(), "ATOM".to_string()],
+            remove: vec![],
+        };
+        sudo(_instance.deps.as_mut(), _instance.env.clone(), msg).unwrap();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::ReservedSymbols {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: ReservedSymbolsResponse = from_binary(&res).unwrap();
+        assert_eq!(res.symbols, vec!["ATOM".to_string(), "HUAHUA".to_string()]);
+
+        // the reserved list is case insensitive and catches look-alikes
+        let mut token_info = new_token_info();
+        token_info.symbol = "HuaHua".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ReservedSymbol { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let mut token_info = new_token_info();
+        token_info.symbol = "A-TOM".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ReservedSymbol { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // once removed the symbol can be used
+        let msg = ExecuteMsg::UpdateReservedSymbols {
+            add: vec![],
+            remove: vec!["atom".to_string()],
+        };
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(&_instance.caller, &[]),
+            msg,
+        )
+        .unwrap();
+
+        let mut token_info = new_token_info();
+        token_info.symbol = "ATOM".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_create_new_token_confusable() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(
+            &_instance.caller,
+            &[_instance.msg.token_creation_fees[0].clone()],
+        );
+
+        // create a new token
+        let msg = ExecuteMsg::CreateToken {
+            token_info: new_token_info(),
+            managed_upgrades: None,
+        };
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap();
+        let reply_msg = Reply {
+            id: 1,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(vec![10, 8, 112, 97, 105, 114, 48, 48, 48, 48].into()),
+            }),
+        };
+        reply(_instance.deps.as_mut(), mock_env(), reply_msg).unwrap();
+
+        // a symbol that only differs by look-alike characters is rejected
+        let mut token_info = new_token_info();
+        token_info.name = "Other Token".to_string();
+        token_info.symbol = "T-T-T".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ConfusableSymbol { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // so is a name that only differs by punctuation or look-alikes
+        let mut token_info = new_token_info();
+        token_info.name = "Test-T0ken".to_string();
+        token_info.symbol = "OTHER".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            msg,
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ConfusableName { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // or only by accents
+        let mut token_info = new_token_info();
+        token_info.name = "Tést Tokèn".to_string();
+        token_info.symbol = "OTHER".to_string();
+        let msg = ExecuteMsg::CreateToken {
+            token_info,
+            managed_upgrades: None,
+        };
+        let _err = execute(_instance.deps.as_mut(), _instance.env.clone(), info, msg).unwrap_err();
+        match _err {
+            ContractError::ConfusableName { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
 }
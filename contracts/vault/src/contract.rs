@@ -0,0 +1,1243 @@
+use crate::error::ContractError;
+use crate::msg::{
+    AutoCompoundResponse, DealResponse, DelegationsResponse, ExecuteMsg, InstantiateMsg,
+    PendingRewardsResponse, QueryMsg, UnbondingEntriesResponse, ValidatorRewards, VotesResponse,
+};
+use crate::state::{
+    Action, AutoCompound, Controller, Deal, DealTerms, Liquidation, Owner, UnbondingEntry,
+    VaultPreferences, Vote, WeightedVoteOption, AUTO_COMPOUND, DEAL, DEFAULT_LIMIT, MAX_LIMIT,
+    UNBONDING_ENTRIES, VAULT_PREFERENCES, VOTES,
+};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_binary, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, DistributionMsg,
+    Env, GovMsg, MessageInfo, Order, Response, StakingMsg, StdError, StdResult, Timestamp, Uint128,
+    VoteOption,
+};
+use cw20::Expiration;
+use cw_storage_plus::Bound;
+use proto_encoding::{encode_bytes_field, encode_varint_field};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:vault";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// The highest share of the compounded rewards paid to the keeper, in percent
+const MAX_KEEPER_FEE: u64 = 5;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    //Store the contract name and version
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // The instantiator is the vaults manager
+    let preferences = VaultPreferences {
+        vault_manager_address: info.sender,
+        unbonding_period: msg.unbonding_period,
+        owner: to_owner(deps.as_ref(), &msg.owner)?,
+        controller: None,
+    };
+    VAULT_PREFERENCES.save(deps.storage, &preferences)?;
+    UNBONDING_ENTRIES.save(deps.storage, &vec![])?;
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", msg.owner))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Delegate { validator, amount } => {
+            execute_delegate(deps, env, info, validator, amount)
+        }
+        ExecuteMsg::Undelegate { validator, amount } => {
+            execute_undelegate(deps, env, info, validator, amount)
+        }
+        ExecuteMsg::Redelegate {
+            src_validator,
+            dst_validator,
+            amount,
+        } => execute_redelegate(deps, env, info, src_validator, dst_validator, amount),
+        ExecuteMsg::ClaimRewards {} => execute_claim_rewards(deps, env, info),
+        ExecuteMsg::WithdrawFunds {
+            denom,
+            amount,
+            recipient,
+        } => execute_withdraw_funds(deps, env, info, denom, amount, recipient),
+        ExecuteMsg::SetController {
+            address,
+            actions_scope,
+            expiration_date,
+            percentage_stake_to_undelegate_at_liquidation,
+        } => execute_set_controller(
+            deps,
+            env,
+            info,
+            address,
+            actions_scope,
+            expiration_date,
+            percentage_stake_to_undelegate_at_liquidation,
+        ),
+        ExecuteMsg::RevokeController {} => execute_revoke_controller(deps, env, info),
+        ExecuteMsg::CreateDeal { terms } => execute_create_deal(deps, env, info, terms),
+        ExecuteMsg::CancelDeal {} => execute_cancel_deal(deps, info),
+        ExecuteMsg::AcceptDeal {} => execute_accept_deal(deps, env, info),
+        ExecuteMsg::RepayLoan {} => execute_repay_loan(deps, info),
+        ExecuteMsg::Liquidate {} => execute_liquidate(deps, env, info),
+        ExecuteMsg::TerminateDeal {} => execute_terminate_deal(deps, env, info),
+        ExecuteMsg::SetAutoCompound { settings } => execute_set_auto_compound(deps, info, settings),
+        ExecuteMsg::Compound {} => execute_compound(deps, env, info),
+        ExecuteMsg::Vote { proposal_id, vote } => {
+            let options = vec![WeightedVoteOption {
+                option: vote,
+                weight: Decimal::one(),
+            }];
+            execute_vote(deps, env, info, proposal_id, options)
+        }
+        ExecuteMsg::VoteWeighted {
+            proposal_id,
+            options,
+        } => execute_vote(deps, env, info, proposal_id, options),
+        ExecuteMsg::ChangeOwner { new_owner } => execute_change_owner(deps, info, new_owner),
+    }
+}
+
+// A new owner can perform every action
+fn to_owner(deps: Deps, address: &str) -> StdResult<Owner> {
+    Ok(Owner {
+        address: deps.api.addr_validate(address)?,
+        actions_scope: Action::all(),
+        is_active: true,
+    })
+}
+
+/**
+ * Make sure the caller is either the active owner or the controller in
+ * effect, and that the action is in its actions_scope
+ */
+fn assert_allowed(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    action: Action,
+) -> Result<VaultPreferences, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+
+    let owner = &preferences.owner;
+    if info.sender == owner.address {
+        if !owner.is_active || !owner.actions_scope.contains(&action) {
+            return Err(ContractError::ActionNotAllowed { action });
+        }
+        return Ok(preferences);
+    }
+
+    match &preferences.controller {
+        Some(controller) if info.sender == controller.address => {
+            if !controller.is_in_effect(&env.block) || !controller.actions_scope.contains(&action) {
+                return Err(ContractError::ActionNotAllowed { action });
+            }
+            Ok(preferences)
+        }
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+fn bonded_coin(deps: Deps, amount: Uint128) -> Result<Coin, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let denom = deps.querier.query_bonded_denom()?;
+    Ok(coin(amount.u128(), denom))
+}
+
+fn execute_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_allowed(deps.as_ref(), &env, &info, Action::Delegate)?;
+    let amount = bonded_coin(deps.as_ref(), amount)?;
+
+    // Delegating withdraws the pending rewards of the validator
+    let rewards_msg = pay_deal_rewards(deps, &env, &[&validator])?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_delegate")
+        .add_attribute("validator", &validator)
+        .add_attribute("amount", amount.to_string())
+        .add_message(StakingMsg::Delegate { validator, amount })
+        .add_messages(rewards_msg))
+}
+
+/**
+ * Record an unbonding entry, dropping the ones that have completed
+ * @return the completion time of the entry
+ */
+fn record_unbonding(
+    deps: DepsMut,
+    env: &Env,
+    preferences: &VaultPreferences,
+    validator: &str,
+    amount: &Coin,
+) -> StdResult<Timestamp> {
+    let completion_time = env.block.time.plus_seconds(preferences.unbonding_period);
+
+    let mut entries = UNBONDING_ENTRIES.load(deps.storage)?;
+    entries.retain(|entry| entry.completion_time > env.block.time);
+    entries.push(UnbondingEntry {
+        validator: validator.to_string(),
+        amount: amount.clone(),
+        completion_time,
+    });
+    UNBONDING_ENTRIES.save(deps.storage, &entries)?;
+
+    Ok(completion_time)
+}
+
+// A loan is active from its acceptance until it is repaid or liquidated
+fn is_loan_active(deps: Deps) -> StdResult<bool> {
+    let deal = DEAL.may_load(deps.storage)?;
+    Ok(matches!(
+        deal,
+        Some(Deal {
+            terms: DealTerms::Loan { .. },
+            counterparty: Some(_),
+            ..
+        })
+    ))
+}
+
+// The staking rewards deal, from its acceptance until its end
+fn active_rewards_deal(deps: Deps, env: &Env) -> StdResult<Option<Deal>> {
+    let deal = DEAL.may_load(deps.storage)?;
+    Ok(deal.filter(
+        |deal| match (&deal.terms, &deal.counterparty, deal.deadline) {
+            (DealTerms::StakingRewards { reward_cap, .. }, Some(_), Some(deadline)) => {
+                env.block.time < deadline && deal.rewards_paid < *reward_cap
+            }
+            _ => false,
+        },
+    ))
+}
+
+/**
+ * Send the rewards withdrawn from validators to the counterparty of an
+ * active staking rewards deal, up to its reward_cap
+ * @return the message sending the rewards, if any
+ */
+fn pay_deal_rewards(deps: DepsMut, env: &Env, validators: &[&str]) -> StdResult<Option<CosmosMsg>> {
+    let mut deal = match active_rewards_deal(deps.as_ref(), env)? {
+        Some(deal) => deal,
+        None => return Ok(None),
+    };
+    let (counterparty, reward_cap) = match (&deal.counterparty, &deal.terms) {
+        (Some(counterparty), DealTerms::StakingRewards { reward_cap, .. }) => {
+            (counterparty.clone(), *reward_cap)
+        }
+        _ => return Ok(None),
+    };
+
+    // Only the bonded denom rewards count towards the cap
+    let denom = deps.querier.query_bonded_denom()?;
+    let mut rewards = Uint128::zero();
+    for validator in validators {
+        if let Some(delegation) = deps
+            .querier
+            .query_delegation(&env.contract.address, *validator)?
+        {
+            rewards += delegation
+                .accumulated_rewards
+                .iter()
+                .filter(|coin| coin.denom == denom)
+                .map(|coin| coin.amount)
+                .sum::<Uint128>();
+        }
+    }
+
+    let amount = rewards.min(reward_cap - deal.rewards_paid);
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    deal.rewards_paid += amount;
+    DEAL.save(deps.storage, &deal)?;
+
+    Ok(Some(
+        BankMsg::Send {
+            to_address: counterparty.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        }
+        .into(),
+    ))
+}
+
+/**
+ * Undelegate from validator and record the unbonding entry
+ */
+fn execute_undelegate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    validator: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let preferences = assert_allowed(deps.as_ref(), &env, &info, Action::Undelegate)?;
+    let amount = bonded_coin(deps.as_ref(), amount)?;
+
+    // The stake is the collateral of an active loan or earns the rewards
+    // of an active staking rewards deal
+    if info.sender == preferences.owner.address {
+        if is_loan_active(deps.as_ref())? {
+            return Err(ContractError::ActiveLoan {});
+        }
+        if active_rewards_deal(deps.as_ref(), &env)?.is_some() {
+            return Err(ContractError::ActiveDeal {});
+        }
+    }
+
+    record_unbonding(deps.branch(), &env, &preferences, &validator, &amount)?;
+
+    // Undelegating withdraws the pending rewards of the validator
+    let rewards_msg = pay_deal_rewards(deps, &env, &[&validator])?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_undelegate")
+        .add_attribute("validator", &validator)
+        .add_attribute("amount", amount.to_string())
+        .add_message(StakingMsg::Undelegate { validator, amount })
+        .add_messages(rewards_msg))
+}
+
+fn execute_redelegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    src_validator: String,
+    dst_validator: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_allowed(deps.as_ref(), &env, &info, Action::Redelegate)?;
+    let amount = bonded_coin(deps.as_ref(), amount)?;
+
+    // Redelegating withdraws the pending rewards of both validators
+    let rewards_msg = pay_deal_rewards(deps, &env, &[&src_validator, &dst_validator])?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_redelegate")
+        .add_attribute("src_validator", &src_validator)
+        .add_attribute("dst_validator", &dst_validator)
+        .add_attribute("amount", amount.to_string())
+        .add_message(StakingMsg::Redelegate {
+            src_validator,
+            dst_validator,
+            amount,
+        })
+        .add_messages(rewards_msg))
+}
+
+/**
+ * Withdraw the rewards of every delegation, they stay in the vault
+ * unless a staking rewards deal is active
+ */
+fn execute_claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_allowed(deps.as_ref(), &env, &info, Action::ClaimRewards)?;
+
+    let delegations = deps
+        .querier
+        .query_all_delegations(env.contract.address.to_string())?;
+    if delegations.is_empty() {
+        return Err(ContractError::NoDelegations {});
+    }
+
+    let validators: Vec<&str> = delegations
+        .iter()
+        .map(|delegation| delegation.validator.as_str())
+        .collect();
+    let rewards_msg = pay_deal_rewards(deps, &env, &validators)?;
+
+    let msgs: Vec<CosmosMsg> = delegations
+        .into_iter()
+        .map(|delegation| {
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: delegation.validator,
+            }
+            .into()
+        })
+        .collect();
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_claim_rewards")
+        .add_messages(msgs)
+        .add_messages(rewards_msg))
+}
+
+fn execute_withdraw_funds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let preferences = assert_allowed(deps.as_ref(), &env, &info, Action::WithdrawFunds)?;
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // The liquidated stake returns to the vault for the lender
+    let liquidating = matches!(
+        DEAL.may_load(deps.storage)?,
+        Some(Deal {
+            liquidation: Some(_),
+            ..
+        })
+    );
+    if liquidating && info.sender == preferences.owner.address {
+        return Err(ContractError::ActiveLoan {});
+    }
+
+    // The bonded denom rewards withdrawn by a delegation change belong to
+    // the counterparty of an active staking rewards deal
+    if active_rewards_deal(deps.as_ref(), &env)?.is_some()
+        && denom == deps.querier.query_bonded_denom()?
+    {
+        return Err(ContractError::ActiveDeal {});
+    }
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender,
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_withdraw_funds")
+        .add_attribute("recipient", recipient.to_string())
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        }))
+}
+
+/**
+ * Grant actions_scope to a controller until expiration_date, the
+ * controller isn't replaced while it is in effect
+ */
+fn execute_set_controller(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    actions_scope: Vec<Action>,
+    expiration_date: Expiration,
+    percentage_stake_to_undelegate_at_liquidation: Decimal,
+) -> Result<Response, ContractError> {
+    let mut preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    if info.sender != preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(controller) = &preferences.controller {
+        if controller.is_in_effect(&env.block) {
+            return Err(ContractError::ControllerInEffect {});
+        }
+    }
+
+    if actions_scope.is_empty()
+        || expiration_date.is_expired(&env.block)
+        || percentage_stake_to_undelegate_at_liquidation > Decimal::one()
+    {
+        return Err(ContractError::InvalidController {});
+    }
+
+    preferences.controller = Some(Controller {
+        address: deps.api.addr_validate(&address)?,
+        actions_scope,
+        expiration_date,
+        percentage_stake_to_undelegate_at_liquidation,
+        is_active: true,
+    });
+    VAULT_PREFERENCES.save(deps.storage, &preferences)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_set_controller")
+        .add_attribute("controller", address)
+        .add_attribute("expiration_date", expiration_date.to_string()))
+}
+
+/**
+ * The controller can give up its rights at any time, the owner
+ * can only clear a controller that is no longer in effect
+ */
+fn execute_revoke_controller(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    let controller = preferences
+        .controller
+        .as_ref()
+        .ok_or(ContractError::NoController {})?;
+
+    let allowed = info.sender == controller.address
+        || (info.sender == preferences.owner.address && !controller.is_in_effect(&env.block));
+    if !allowed {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    preferences.controller = None;
+    VAULT_PREFERENCES.save(deps.storage, &preferences)?;
+
+    Ok(Response::new().add_attribute("method", "execute_revoke_controller"))
+}
+
+/**
+ * Offer a deal on the vault, refused while a controller is in effect
+ * since the counterparty becomes the controller
+ */
+fn execute_create_deal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    terms: DealTerms,
+) -> Result<Response, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    if info.sender != preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if DEAL.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::DealExists {});
+    }
+    if let Some(controller) = &preferences.controller {
+        if controller.is_in_effect(&env.block) {
+            return Err(ContractError::ControllerInEffect {});
+        }
+    }
+
+    match &terms {
+        DealTerms::Loan {
+            amount,
+            repayment_amount,
+            duration,
+            percentage_stake_to_undelegate_at_liquidation,
+        } => {
+            if amount.amount.is_zero()
+                || repayment_amount.is_zero()
+                || *duration == 0
+                || *percentage_stake_to_undelegate_at_liquidation > Decimal::one()
+            {
+                return Err(ContractError::InvalidDeal {});
+            }
+        }
+        DealTerms::StakingRewards {
+            amount,
+            duration,
+            reward_cap,
+        } => {
+            if amount.amount.is_zero() || *duration == 0 || reward_cap.is_zero() {
+                return Err(ContractError::InvalidDeal {});
+            }
+        }
+    }
+
+    let deal = Deal {
+        terms,
+        counterparty: None,
+        deadline: None,
+        liquidation: None,
+        rewards_paid: Uint128::zero(),
+    };
+    DEAL.save(deps.storage, &deal)?;
+
+    Ok(Response::new().add_attribute("method", "execute_create_deal"))
+}
+
+fn execute_cancel_deal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    if info.sender != preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let deal = DEAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoDeal {})?;
+    if deal.counterparty.is_some() {
+        return Err(ContractError::DealAlreadyAccepted {});
+    }
+    DEAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("method", "execute_cancel_deal"))
+}
+
+// The funds sent must be exactly the expected coin
+fn assert_funds(info: &MessageInfo, expected: &Coin) -> Result<(), ContractError> {
+    if info.funds != [expected.clone()] {
+        return Err(ContractError::IncorrectFunds {
+            expected: expected.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/**
+ * Accept the deal on the vault, the amount sent goes to the owner and
+ * the counterparty becomes the controller until the deal is settled
+ */
+fn execute_accept_deal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    let mut deal = DEAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoDeal {})?;
+    if deal.counterparty.is_some() {
+        return Err(ContractError::DealAlreadyAccepted {});
+    }
+    if info.sender == preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(controller) = &preferences.controller {
+        if controller.is_in_effect(&env.block) {
+            return Err(ContractError::ControllerInEffect {});
+        }
+    }
+
+    let (amount, controller) = match &deal.terms {
+        DealTerms::Loan {
+            amount,
+            duration,
+            percentage_stake_to_undelegate_at_liquidation,
+            ..
+        } => {
+            deal.deadline = Some(env.block.time.plus_seconds(*duration));
+            let controller = Controller {
+                address: info.sender.clone(),
+                actions_scope: vec![Action::ClaimRewards],
+                expiration_date: Expiration::Never {},
+                percentage_stake_to_undelegate_at_liquidation:
+                    *percentage_stake_to_undelegate_at_liquidation,
+                is_active: true,
+            };
+            (amount.clone(), controller)
+        }
+        DealTerms::StakingRewards {
+            amount, duration, ..
+        } => {
+            let deadline = env.block.time.plus_seconds(*duration);
+            deal.deadline = Some(deadline);
+            let controller = Controller {
+                address: info.sender.clone(),
+                actions_scope: vec![Action::ClaimRewards],
+                expiration_date: Expiration::AtTime(deadline),
+                percentage_stake_to_undelegate_at_liquidation: Decimal::zero(),
+                is_active: true,
+            };
+            (amount.clone(), controller)
+        }
+    };
+    assert_funds(&info, &amount)?;
+
+    deal.counterparty = Some(info.sender.clone());
+    DEAL.save(deps.storage, &deal)?;
+    preferences.controller = Some(controller);
+    VAULT_PREFERENCES.save(deps.storage, &preferences)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_accept_deal")
+        .add_attribute("counterparty", info.sender)
+        .add_message(BankMsg::Send {
+            to_address: preferences.owner.address.to_string(),
+            amount: vec![amount],
+        }))
+}
+
+// Remove the settled deal and its controller
+fn close_deal(deps: DepsMut) -> StdResult<()> {
+    DEAL.remove(deps.storage);
+    VAULT_PREFERENCES.update(deps.storage, |mut preferences| -> StdResult<_> {
+        preferences.controller = None;
+        Ok(preferences)
+    })?;
+    Ok(())
+}
+
+/**
+ * Send the repayment_amount to the lender and close the loan,
+ * until the lender starts a liquidation
+ */
+fn execute_repay_loan(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let deal = DEAL.may_load(deps.storage)?;
+    let (lender, repayment) = match deal {
+        Some(Deal {
+            terms:
+                DealTerms::Loan {
+                    amount,
+                    repayment_amount,
+                    ..
+                },
+            counterparty: Some(lender),
+            liquidation: None,
+            ..
+        }) => (lender, coin(repayment_amount.u128(), amount.denom)),
+        _ => return Err(ContractError::NoActiveLoan {}),
+    };
+    assert_funds(&info, &repayment)?;
+
+    close_deal(deps)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_repay_loan")
+        .add_message(BankMsg::Send {
+            to_address: lender.to_string(),
+            amount: vec![repayment],
+        }))
+}
+
+/**
+ * Undelegate percentage_stake_to_undelegate_at_liquidation of every
+ * delegation of an overdue loan, then send it to the lender once unbonded
+ */
+fn execute_liquidate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    let mut deal = DEAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoActiveLoan {})?;
+    // The percentage is part of the accepted terms, the lender's controller
+    // may have been revoked since
+    let (lender, deadline, percentage) = match (&deal.terms, &deal.counterparty, deal.deadline) {
+        (
+            DealTerms::Loan {
+                percentage_stake_to_undelegate_at_liquidation,
+                ..
+            },
+            Some(lender),
+            Some(deadline),
+        ) => (
+            lender.clone(),
+            deadline,
+            *percentage_stake_to_undelegate_at_liquidation,
+        ),
+        _ => return Err(ContractError::NoActiveLoan {}),
+    };
+    if info.sender != lender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time < deadline {
+        return Err(ContractError::LoanNotDue {});
+    }
+
+    // The unbonded stake is sent to the lender and the loan is closed
+    if let Some(liquidation) = deal.liquidation {
+        if env.block.time < liquidation.completion_time {
+            return Err(ContractError::LiquidationNotComplete {});
+        }
+        let balance = deps
+            .querier
+            .query_balance(&env.contract.address, &liquidation.amount.denom)?;
+        let amount = coin(
+            liquidation.amount.amount.min(balance.amount).u128(),
+            liquidation.amount.denom,
+        );
+        close_deal(deps)?;
+
+        let mut res = Response::new().add_attribute("method", "execute_liquidate");
+        if !amount.amount.is_zero() {
+            res = res.add_message(BankMsg::Send {
+                to_address: lender.to_string(),
+                amount: vec![amount],
+            });
+        }
+        return Ok(res);
+    }
+
+    let delegations = deps
+        .querier
+        .query_all_delegations(env.contract.address.to_string())?;
+    let denom = deps.querier.query_bonded_denom()?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    let mut total = Uint128::zero();
+    let mut completion_time = env.block.time;
+    for delegation in delegations {
+        let amount = delegation.amount.amount * percentage;
+        if amount.is_zero() {
+            continue;
+        }
+        let amount = coin(amount.u128(), &denom);
+        completion_time = record_unbonding(
+            deps.branch(),
+            &env,
+            &preferences,
+            &delegation.validator,
+            &amount,
+        )?;
+        total += amount.amount;
+        msgs.push(
+            StakingMsg::Undelegate {
+                validator: delegation.validator,
+                amount,
+            }
+            .into(),
+        );
+    }
+
+    deal.liquidation = Some(Liquidation {
+        amount: coin(total.u128(), denom),
+        completion_time,
+    });
+    DEAL.save(deps.storage, &deal)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_liquidate")
+        .add_attribute("amount", total)
+        .add_messages(msgs))
+}
+
+/**
+ * The share of the upfront amount of an active staking rewards deal refunded
+ * on early termination, whichever of the remaining time and the remaining
+ * reward cap is the smallest
+ */
+fn termination_amount(deps: Deps, env: &Env) -> StdResult<Option<Coin>> {
+    let deal = match active_rewards_deal(deps, env)? {
+        Some(deal) => deal,
+        None => return Ok(None),
+    };
+    match (deal.terms, deal.deadline) {
+        (
+            DealTerms::StakingRewards {
+                amount,
+                duration,
+                reward_cap,
+            },
+            Some(deadline),
+        ) => {
+            let remaining_time =
+                Decimal::from_ratio(deadline.seconds() - env.block.time.seconds(), duration);
+            let remaining_rewards = Decimal::from_ratio(reward_cap - deal.rewards_paid, reward_cap);
+            Ok(Some(coin(
+                (amount.amount * remaining_time.min(remaining_rewards)).u128(),
+                amount.denom,
+            )))
+        }
+        _ => Ok(None),
+    }
+}
+
+/**
+ * Close a staking rewards deal and remove its controller. Once the deal has
+ * ended either party can close it, before that the owner must refund the
+ * termination_amount to the counterparty and gets back the rest of the funds sent
+ */
+fn execute_terminate_deal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    let deal = DEAL.may_load(deps.storage)?;
+    let counterparty = match deal {
+        Some(Deal {
+            terms: DealTerms::StakingRewards { .. },
+            counterparty: Some(counterparty),
+            ..
+        }) => counterparty,
+        _ => return Err(ContractError::NoDeal {}),
+    };
+
+    let mut res = Response::new().add_attribute("method", "execute_terminate_deal");
+    match termination_amount(deps.as_ref(), &env)? {
+        None => {
+            if info.sender != preferences.owner.address && info.sender != counterparty {
+                return Err(ContractError::Unauthorized {});
+            }
+        }
+        Some(refund) => {
+            if info.sender != preferences.owner.address {
+                return Err(ContractError::DealNotEnded {});
+            }
+            let paid = info
+                .funds
+                .iter()
+                .find(|coin| coin.denom == refund.denom && coin.amount >= refund.amount)
+                .ok_or_else(|| ContractError::IncorrectFunds {
+                    expected: refund.to_string(),
+                })?;
+
+            // Send back anything above the refund
+            let rest: Vec<Coin> = info
+                .funds
+                .iter()
+                .map(|coin| match coin.denom == paid.denom {
+                    true => Coin {
+                        denom: coin.denom.clone(),
+                        amount: coin.amount - refund.amount,
+                    },
+                    false => coin.clone(),
+                })
+                .filter(|coin| !coin.amount.is_zero())
+                .collect();
+            if !refund.amount.is_zero() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: counterparty.to_string(),
+                    amount: vec![refund],
+                });
+            }
+            if !rest.is_empty() {
+                res = res.add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: rest,
+                });
+            }
+        }
+    }
+    close_deal(deps)?;
+
+    Ok(res)
+}
+
+fn execute_set_auto_compound(
+    deps: DepsMut,
+    info: MessageInfo,
+    settings: Option<AutoCompound>,
+) -> Result<Response, ContractError> {
+    let preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    if info.sender != preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match settings {
+        Some(settings) => {
+            let max_keeper_fee = Decimal::percent(MAX_KEEPER_FEE);
+            if settings.validators.is_empty() || settings.keeper_fee > max_keeper_fee {
+                return Err(ContractError::InvalidAutoCompound { max_keeper_fee });
+            }
+            for validator in settings.validators.iter() {
+                if deps.querier.query_validator(validator)?.is_none() {
+                    return Err(StdError::not_found(format!("Validator {}", validator)).into());
+                }
+            }
+            AUTO_COMPOUND.save(deps.storage, &settings)?;
+        }
+        None => AUTO_COMPOUND.remove(deps.storage),
+    }
+
+    Ok(Response::new().add_attribute("method", "execute_set_auto_compound"))
+}
+
+/**
+ * Claim the rewards of every delegation once they reach min_threshold,
+ * pay the keeper_fee to the caller and delegate the rest evenly
+ * to the auto compound validators
+ */
+fn execute_compound(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let settings = AUTO_COMPOUND
+        .may_load(deps.storage)?
+        .ok_or(ContractError::AutoCompoundDisabled {})?;
+
+    // The rewards belong to the counterparty of a staking rewards deal
+    if active_rewards_deal(deps.as_ref(), &env)?.is_some() {
+        return Err(ContractError::ActiveDeal {});
+    }
+
+    let denom = deps.querier.query_bonded_denom()?;
+    let pending = query_pending_rewards(deps.as_ref(), env)?;
+    let total = pending
+        .total
+        .iter()
+        .find(|coin| coin.denom == denom)
+        .map(|coin| coin.amount)
+        .unwrap_or_default();
+    if total.is_zero() || total < settings.min_threshold {
+        return Err(ContractError::BelowThreshold {
+            min_threshold: settings.min_threshold,
+        });
+    }
+
+    let mut msgs: Vec<CosmosMsg> = pending
+        .rewards
+        .into_iter()
+        .map(|rewards| {
+            DistributionMsg::WithdrawDelegatorReward {
+                validator: rewards.validator,
+            }
+            .into()
+        })
+        .collect();
+
+    let fee = total * settings.keeper_fee;
+    if !fee.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(fee.u128(), &denom)],
+            }
+            .into(),
+        );
+    }
+
+    // The first validator gets the remainder of the split
+    let amount = total - fee;
+    let count = Uint128::from(settings.validators.len() as u128);
+    let share = amount / count;
+    let remainder = amount - share * count;
+    for (i, validator) in settings.validators.into_iter().enumerate() {
+        let amount = match i {
+            0 => share + remainder,
+            _ => share,
+        };
+        if !amount.is_zero() {
+            msgs.push(
+                StakingMsg::Delegate {
+                    validator,
+                    amount: coin(amount.u128(), &denom),
+                }
+                .into(),
+            );
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_compound")
+        .add_attribute("rewards", total)
+        .add_attribute("keeper_fee", fee)
+        .add_messages(msgs))
+}
+
+/**
+ * Vote on a governance proposal with the stake of the vault and record
+ * the vote. A single option is sent as a GovMsg::Vote, several as
+ * a MsgVoteWeighted since cosmwasm has no weighted vote message
+ */
+fn execute_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    options: Vec<WeightedVoteOption>,
+) -> Result<Response, ContractError> {
+    let preferences = assert_allowed(deps.as_ref(), &env, &info, Action::Vote)?;
+    if info.sender != preferences.owner.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let total = options
+        .iter()
+        .fold(Decimal::zero(), |total, option| total + option.weight);
+    let distinct = options.iter().enumerate().all(|(i, option)| {
+        !option.weight.is_zero() && options[..i].iter().all(|o| o.option != option.option)
+    });
+    if total != Decimal::one() || !distinct {
+        return Err(ContractError::InvalidVote {});
+    }
+
+    let msg: CosmosMsg = match options.as_slice() {
+        [option] => GovMsg::Vote {
+            proposal_id,
+            vote: option.option.clone(),
+        }
+        .into(),
+        _ => CosmosMsg::Stargate {
+            type_url: "/cosmos.gov.v1beta1.MsgVoteWeighted".to_string(),
+            value: encode_vote_weighted(proposal_id, env.contract.address.as_str(), &options),
+        },
+    };
+
+    VOTES.save(
+        deps.storage,
+        proposal_id,
+        &Vote {
+            proposal_id,
+            options,
+            voted_at: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_vote")
+        .add_attribute("proposal_id", proposal_id.to_string())
+        .add_message(msg))
+}
+
+/**
+ * Protobuf encoding of cosmos.gov.v1beta1.MsgVoteWeighted, the weights are
+ * sdk.Dec which go on the wire as their 18 decimals atomics
+ */
+fn encode_vote_weighted(proposal_id: u64, voter: &str, options: &[WeightedVoteOption]) -> Binary {
+    let mut buf = vec![];
+    encode_varint_field(&mut buf, 1, proposal_id);
+    encode_bytes_field(&mut buf, 2, voter.as_bytes());
+    for option in options {
+        let option_number = match option.option {
+            VoteOption::Yes => 1,
+            VoteOption::Abstain => 2,
+            VoteOption::No => 3,
+            VoteOption::NoWithVeto => 4,
+        };
+        let mut weighted_option = vec![];
+        encode_varint_field(&mut weighted_option, 1, option_number);
+        encode_bytes_field(
+            &mut weighted_option,
+            2,
+            option.weight.atomics().to_string().as_bytes(),
+        );
+        encode_bytes_field(&mut buf, 3, &weighted_option);
+    }
+    Binary(buf)
+}
+
+/**
+ * Called by the vaults manager when the vault changes hands, only the
+ * controller of an accepted deal stays in place
+ */
+fn execute_change_owner(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_owner: String,
+) -> Result<Response, ContractError> {
+    let mut preferences = VAULT_PREFERENCES.load(deps.storage)?;
+    if info.sender != preferences.vault_manager_address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // A deal that hasn't been accepted was offered by the previous owner
+    let deal = DEAL.may_load(deps.storage)?;
+    let counterparty = match deal {
+        Some(Deal {
+            counterparty: None, ..
+        }) => {
+            DEAL.remove(deps.storage);
+            None
+        }
+        Some(deal) => deal.counterparty,
+        None => None,
+    };
+
+    // Any other controller was granted by the previous owner and would
+    // otherwise keep its rights over the new owner's vault
+    if let Some(controller) = &preferences.controller {
+        if Some(&controller.address) != counterparty.as_ref() {
+            preferences.controller = None;
+        }
+    }
+
+    preferences.owner = to_owner(deps.as_ref(), &new_owner)?;
+    VAULT_PREFERENCES.save(deps.storage, &preferences)?;
+
+    // The auto compound settings and keeper fee were chosen by the previous owner
+    AUTO_COMPOUND.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_change_owner")
+        .add_attribute("owner", new_owner))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    // Match and route the query message to the appropriate handler
+    match msg {
+        QueryMsg::Preferences {} => to_binary(&VAULT_PREFERENCES.load(deps.storage)?),
+        QueryMsg::Delegations {} => to_binary(&query_delegations(deps, env)?),
+        QueryMsg::PendingRewards {} => to_binary(&query_pending_rewards(deps, env)?),
+        QueryMsg::UnbondingEntries {} => to_binary(&query_unbonding_entries(deps, env)?),
+        QueryMsg::Deal {} => to_binary(&query_deal(deps, env)?),
+        QueryMsg::Votes { start_after, limit } => {
+            to_binary(&query_votes(deps, start_after, limit)?)
+        }
+        QueryMsg::AutoCompound {} => to_binary(&AutoCompoundResponse {
+            settings: AUTO_COMPOUND.may_load(deps.storage)?,
+        }),
+    }
+}
+
+fn query_delegations(deps: Deps, env: Env) -> StdResult<DelegationsResponse> {
+    let delegations = deps.querier.query_all_delegations(env.contract.address)?;
+    Ok(DelegationsResponse { delegations })
+}
+
+/**
+ * The rewards accumulated by every delegation of the vault
+ * and their total per denom
+ */
+fn query_pending_rewards(deps: Deps, env: Env) -> StdResult<PendingRewardsResponse> {
+    let delegations = deps
+        .querier
+        .query_all_delegations(env.contract.address.to_string())?;
+
+    let mut rewards = vec![];
+    let mut total: Vec<Coin> = vec![];
+    for delegation in delegations {
+        let accumulated_rewards = deps
+            .querier
+            .query_delegation(env.contract.address.to_string(), &delegation.validator)?
+            .map(|full| full.accumulated_rewards)
+            .unwrap_or_default();
+
+        for reward in accumulated_rewards.iter() {
+            match total.iter_mut().find(|c| c.denom == reward.denom) {
+                Some(c) => c.amount += reward.amount,
+                None => total.push(reward.clone()),
+            }
+        }
+        rewards.push(ValidatorRewards {
+            validator: delegation.validator,
+            rewards: accumulated_rewards,
+        });
+    }
+
+    Ok(PendingRewardsResponse { rewards, total })
+}
+
+fn query_unbonding_entries(deps: Deps, env: Env) -> StdResult<UnbondingEntriesResponse> {
+    let entries = UNBONDING_ENTRIES
+        .load(deps.storage)?
+        .into_iter()
+        .filter(|entry| entry.completion_time > env.block.time)
+        .collect();
+    Ok(UnbondingEntriesResponse { entries })
+}
+
+fn query_deal(deps: Deps, env: Env) -> StdResult<DealResponse> {
+    Ok(DealResponse {
+        deal: DEAL.may_load(deps.storage)?,
+        termination_amount: termination_amount(deps, &env)?,
+    })
+}
+
+fn query_votes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<VotesResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let votes: StdResult<Vec<_>> = VOTES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, vote)| vote))
+        .collect();
+
+    Ok(VotesResponse { votes: votes? })
+}
@@ -0,0 +1,76 @@
+use cosmwasm_std::{Decimal, StdError, Uint128};
+use thiserror::Error;
+
+use crate::state::Action;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("The caller isn't allowed to {action}")]
+    ActionNotAllowed { action: Action },
+
+    #[error("Amount must be non zero")]
+    ZeroAmount {},
+
+    #[error("The vault already has a controller in effect")]
+    ControllerInEffect {},
+
+    #[error("The vault has no controller")]
+    NoController {},
+
+    #[error("controller actions_scope must not be empty, expiration_date must be in the future and percentage_stake_to_undelegate_at_liquidation at most 1")]
+    InvalidController {},
+
+    #[error("The vault already has a deal")]
+    DealExists {},
+
+    #[error("The vault has no deal")]
+    NoDeal {},
+
+    #[error("The deal has already been accepted")]
+    DealAlreadyAccepted {},
+
+    #[error("The vault has no active loan")]
+    NoActiveLoan {},
+
+    #[error("Not allowed while a loan is active on the vault")]
+    ActiveLoan {},
+
+    #[error("deal amounts and duration must be non zero and percentage_stake_to_undelegate_at_liquidation at most 1")]
+    InvalidDeal {},
+
+    #[error("Incorrect funds sent, expected {expected}")]
+    IncorrectFunds { expected: String },
+
+    #[error("The loan can be liquidated once its deadline has passed")]
+    LoanNotDue {},
+
+    #[error("The liquidated stake is still unbonding")]
+    LiquidationNotComplete {},
+
+    #[error("The stake is committed to an active staking rewards deal")]
+    ActiveDeal {},
+
+    #[error("The staking rewards deal can't be terminated by the caller yet")]
+    DealNotEnded {},
+
+    #[error("The vault has no delegations")]
+    NoDelegations {},
+
+    #[error("Auto compound needs validators and a keeper_fee of at most {max_keeper_fee}")]
+    InvalidAutoCompound { max_keeper_fee: Decimal },
+
+    #[error("Auto compound is turned off")]
+    AutoCompoundDisabled {},
+
+    #[error("The pending rewards are below the min_threshold of {min_threshold}")]
+    BelowThreshold { min_threshold: Uint128 },
+
+    #[error("Vote options must be distinct with weights adding up to 1")]
+    InvalidVote {},
+}
@@ -0,0 +1,916 @@
+#[cfg(test)]
+mod tests {
+    use crate::msg::{
+        DealResponse, DelegationsResponse, ExecuteMsg, InstantiateMsg, PendingRewardsResponse,
+        QueryMsg, UnbondingEntriesResponse, VotesResponse,
+    };
+    use crate::state::{Action, DealTerms, WeightedVoteOption};
+    use crate::ContractError;
+
+    use anyhow::{bail, Result as AnyResult};
+    use cosmwasm_std::{
+        coin, coins, to_binary, Addr, AllDelegationsResponse, AllValidatorsResponse, Api, BankMsg,
+        Binary, BlockInfo, BondedDenomResponse, CustomQuery, Decimal, Delegation,
+        DelegationResponse, Empty, FullDelegation, Querier, StakingMsg, StakingQuery, Storage,
+        Uint128, VoteOption,
+    };
+    use cw20::Expiration;
+    use cw_multi_test::{
+        App, AppBuilder, AppResponse, BankKeeper, Contract, ContractWrapper, CosmosRouter,
+        Executor, FailingDistribution, Module, Staking, StakingSudo, WasmKeeper,
+    };
+    use cw_storage_plus::Map;
+    use schemars::JsonSchema;
+    use serde::de::DeserializeOwned;
+
+    const MANAGER: &str = "vaults_manager";
+    const OWNER: &str = "owner";
+    const BONDED_DENOM: &str = "ustake";
+    const UNBONDING_PERIOD: u64 = 1_209_600;
+    const SUPPLY: u128 = 500_000_000u128;
+    const VALIDATORS: [&str; 2] = ["validator1", "validator2"];
+
+    // The test staking module holds the bonded tokens and the reward pool
+    const STAKING_MODULE: &str = "staking_module";
+
+    // (delegator, validator) -> (amount, height of the last rewards withdrawal)
+    const DELEGATIONS: Map<(&Addr, &str), (Uint128, u64)> = Map::new("test_delegations");
+
+    /**
+     * cw-multi-test 0.14 only ships a failing staking module, this one keeps
+     * the delegations in storage and accrues 1% of the delegated amount as
+     * rewards per block. The distribution module can't be replaced, so the
+     * rewards are only paid when a delegation changes, like on chain
+     */
+    struct TestStaking;
+
+    fn pending_rewards(storage: &dyn Storage, block: &BlockInfo, key: (&Addr, &str)) -> Uint128 {
+        match DELEGATIONS.may_load(storage, key).unwrap() {
+            Some((amount, height)) => amount.multiply_ratio(block.height - height, 100u128),
+            None => Uint128::zero(),
+        }
+    }
+
+    fn send<ExecC, QueryC>(
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        from: Addr,
+        to: &Addr,
+        amount: Uint128,
+    ) -> AnyResult<()>
+    where
+        ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        if !amount.is_zero() {
+            let msg = BankMsg::Send {
+                to_address: to.to_string(),
+                amount: coins(amount.u128(), BONDED_DENOM),
+            };
+            router.execute(api, storage, block, from, msg.into())?;
+        }
+        Ok(())
+    }
+
+    // Pay the pending rewards and move the delegation to `amount`,
+    // like the chain does on every delegation change
+    #[allow(clippy::too_many_arguments)]
+    fn update_delegation<ExecC, QueryC>(
+        api: &dyn Api,
+        storage: &mut dyn Storage,
+        router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+        block: &BlockInfo,
+        delegator: &Addr,
+        validator: &str,
+        amount: impl Fn(Uint128) -> AnyResult<Uint128>,
+    ) -> AnyResult<()>
+    where
+        ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+        QueryC: CustomQuery + DeserializeOwned + 'static,
+    {
+        if !VALIDATORS.contains(&validator) {
+            bail!("Unknown validator {}", validator);
+        }
+        let rewards = pending_rewards(storage, block, (delegator, validator));
+        send(
+            api,
+            storage,
+            router,
+            block,
+            Addr::unchecked(STAKING_MODULE),
+            delegator,
+            rewards,
+        )?;
+        let current = DELEGATIONS
+            .may_load(storage, (delegator, validator))?
+            .map(|(amount, _)| amount)
+            .unwrap_or_default();
+        DELEGATIONS.save(
+            storage,
+            (delegator, validator),
+            &(amount(current)?, block.height),
+        )?;
+        Ok(())
+    }
+
+    impl Staking for TestStaking {}
+
+    impl Module for TestStaking {
+        type ExecT = StakingMsg;
+        type QueryT = StakingQuery;
+        type SudoT = StakingSudo;
+
+        fn execute<ExecC, QueryC>(
+            &self,
+            api: &dyn Api,
+            storage: &mut dyn Storage,
+            router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            block: &BlockInfo,
+            sender: Addr,
+            msg: StakingMsg,
+        ) -> AnyResult<AppResponse>
+        where
+            ExecC: std::fmt::Debug + Clone + PartialEq + JsonSchema + DeserializeOwned + 'static,
+            QueryC: CustomQuery + DeserializeOwned + 'static,
+        {
+            let module = Addr::unchecked(STAKING_MODULE);
+            match msg {
+                StakingMsg::Delegate { validator, amount } => {
+                    send(
+                        api,
+                        storage,
+                        router,
+                        block,
+                        sender.clone(),
+                        &module,
+                        amount.amount,
+                    )?;
+                    update_delegation(api, storage, router, block, &sender, &validator, |d| {
+                        Ok(d + amount.amount)
+                    })?;
+                }
+                // The tokens stay in the module, tests release them once unbonded
+                StakingMsg::Undelegate { validator, amount } => {
+                    update_delegation(api, storage, router, block, &sender, &validator, |d| {
+                        Ok(d.checked_sub(amount.amount)?)
+                    })?;
+                }
+                StakingMsg::Redelegate {
+                    src_validator,
+                    dst_validator,
+                    amount,
+                } => {
+                    update_delegation(api, storage, router, block, &sender, &src_validator, |d| {
+                        Ok(d.checked_sub(amount.amount)?)
+                    })?;
+                    update_delegation(api, storage, router, block, &sender, &dst_validator, |d| {
+                        Ok(d + amount.amount)
+                    })?;
+                }
+                msg => bail!("Unexpected staking msg {:?}", msg),
+            }
+            Ok(AppResponse::default())
+        }
+
+        fn sudo<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            msg: StakingSudo,
+        ) -> AnyResult<AppResponse> {
+            bail!("Unexpected sudo msg {:?}", msg)
+        }
+
+        fn query(
+            &self,
+            _api: &dyn Api,
+            storage: &dyn Storage,
+            _querier: &dyn Querier,
+            block: &BlockInfo,
+            request: StakingQuery,
+        ) -> AnyResult<Binary> {
+            let to_delegation = |delegator: &Addr, validator: &str, amount: Uint128| Delegation {
+                delegator: delegator.clone(),
+                validator: validator.to_string(),
+                amount: coin(amount.u128(), BONDED_DENOM),
+            };
+            let res = match request {
+                StakingQuery::BondedDenom {} => to_binary(&BondedDenomResponse {
+                    denom: BONDED_DENOM.to_string(),
+                })?,
+                StakingQuery::AllDelegations { delegator } => {
+                    let delegator = Addr::unchecked(delegator);
+                    let delegations = VALIDATORS
+                        .iter()
+                        .filter_map(|validator| {
+                            let (amount, _) = DELEGATIONS
+                                .may_load(storage, (&delegator, validator))
+                                .ok()??;
+                            Some(to_delegation(&delegator, validator, amount))
+                        })
+                        .filter(|delegation| !delegation.amount.amount.is_zero())
+                        .collect();
+                    to_binary(&AllDelegationsResponse { delegations })?
+                }
+                StakingQuery::Delegation {
+                    delegator,
+                    validator,
+                } => {
+                    let delegator = Addr::unchecked(delegator);
+                    let key = (&delegator, validator.as_str());
+                    let delegation =
+                        DELEGATIONS
+                            .may_load(storage, key)?
+                            .map(|(amount, _)| FullDelegation {
+                                delegator: delegator.clone(),
+                                validator: validator.clone(),
+                                amount: coin(amount.u128(), BONDED_DENOM),
+                                can_redelegate: coin(amount.u128(), BONDED_DENOM),
+                                accumulated_rewards: coins(
+                                    pending_rewards(storage, block, key).u128(),
+                                    BONDED_DENOM,
+                                ),
+                            });
+                    to_binary(&DelegationResponse { delegation })?
+                }
+                StakingQuery::AllValidators {} => {
+                    to_binary(&AllValidatorsResponse { validators: vec![] })?
+                }
+                request => bail!("Unexpected staking query {:?}", request),
+            };
+            Ok(res)
+        }
+    }
+
+    // Stands for the default custom module, which cw-multi-test doesn't export
+    struct NoCustom;
+
+    impl Module for NoCustom {
+        type ExecT = Empty;
+        type QueryT = Empty;
+        type SudoT = Empty;
+
+        fn execute<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            _sender: Addr,
+            msg: Empty,
+        ) -> AnyResult<AppResponse> {
+            bail!("Unexpected custom msg {:?}", msg)
+        }
+
+        fn sudo<ExecC, QueryC>(
+            &self,
+            _api: &dyn Api,
+            _storage: &mut dyn Storage,
+            _router: &dyn CosmosRouter<ExecC = ExecC, QueryC = QueryC>,
+            _block: &BlockInfo,
+            msg: Empty,
+        ) -> AnyResult<AppResponse> {
+            bail!("Unexpected custom sudo msg {:?}", msg)
+        }
+
+        fn query(
+            &self,
+            _api: &dyn Api,
+            _storage: &dyn Storage,
+            _querier: &dyn Querier,
+            _block: &BlockInfo,
+            request: Empty,
+        ) -> AnyResult<Binary> {
+            bail!("Unexpected custom query {:?}", request)
+        }
+    }
+
+    type TestApp = App<
+        BankKeeper,
+        cosmwasm_std::testing::MockApi,
+        cosmwasm_std::testing::MockStorage,
+        NoCustom,
+        WasmKeeper<Empty, Empty>,
+        TestStaking,
+        FailingDistribution,
+    >;
+
+    struct InstantiationResponse {
+        app: TestApp,
+        c_addr: Addr,
+    }
+
+    fn contract_template() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(
+            crate::contract::execute,
+            crate::contract::instantiate,
+            crate::contract::query,
+        ))
+    }
+
+    fn mock_app() -> TestApp {
+        AppBuilder::new()
+            .with_custom(NoCustom)
+            .with_staking(TestStaking)
+            .build(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(STAKING_MODULE),
+                        coins(SUPPLY, BONDED_DENOM),
+                    )
+                    .unwrap();
+                router
+                    .bank
+                    .init_balance(
+                        storage,
+                        &Addr::unchecked(OWNER),
+                        coins(SUPPLY, BONDED_DENOM),
+                    )
+                    .unwrap();
+            })
+    }
+
+    fn mock_instantiate() -> InstantiationResponse {
+        let mut app = mock_app();
+        let template_id = app.store_code(contract_template());
+
+        let msg = InstantiateMsg {
+            owner: OWNER.to_string(),
+            unbonding_period: UNBONDING_PERIOD,
+        };
+        let c_addr = app
+            .instantiate_contract(
+                template_id,
+                Addr::unchecked(MANAGER),
+                &msg,
+                &[],
+                "vault",
+                None,
+            )
+            .unwrap();
+
+        // fund the vault
+        app.send_tokens(
+            Addr::unchecked(OWNER),
+            c_addr.clone(),
+            &coins(10_000, BONDED_DENOM),
+        )
+        .unwrap();
+
+        InstantiationResponse { app, c_addr }
+    }
+
+    fn execute_as_owner(instance: &mut InstantiationResponse, msg: ExecuteMsg) -> AppResponse {
+        instance
+            .app
+            .execute_contract(Addr::unchecked(OWNER), instance.c_addr.clone(), &msg, &[])
+            .unwrap()
+    }
+
+    fn balance(instance: &InstantiationResponse, address: &str) -> u128 {
+        instance
+            .app
+            .wrap()
+            .query_balance(address, BONDED_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    #[test]
+    fn test_staking_flow() {
+        let mut _instance = mock_instantiate();
+        let vault = _instance.c_addr.to_string();
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(6_000u128),
+            },
+        );
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Redelegate {
+                src_validator: "validator1".to_string(),
+                dst_validator: "validator2".to_string(),
+                amount: Uint128::from(2_000u128),
+            },
+        );
+        assert_eq!(balance(&_instance, &vault), 4_000);
+
+        let res: DelegationsResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::Delegations {})
+            .unwrap();
+        assert_eq!(
+            res.delegations
+                .iter()
+                .map(|d| (d.validator.as_str(), d.amount.amount.u128()))
+                .collect::<Vec<_>>(),
+            vec![("validator1", 4_000), ("validator2", 2_000)]
+        );
+
+        // 1% of the delegations accrues per block
+        _instance.app.update_block(|block| block.height += 5);
+        let res: PendingRewardsResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::PendingRewards {})
+            .unwrap();
+        assert_eq!(res.total, coins(300, BONDED_DENOM));
+
+        // the undelegation pays the rewards of the delegation
+        // and is tracked until it completes
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Undelegate {
+                validator: "validator2".to_string(),
+                amount: Uint128::from(2_000u128),
+            },
+        );
+        let res: UnbondingEntriesResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::UnbondingEntries {})
+            .unwrap();
+        assert_eq!(res.entries.len(), 1);
+        assert_eq!(res.entries[0].amount, coin(2_000, BONDED_DENOM));
+        assert_eq!(balance(&_instance, &vault), 4_100);
+
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(UNBONDING_PERIOD));
+        let res: UnbondingEntriesResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::UnbondingEntries {})
+            .unwrap();
+        assert_eq!(res.entries, vec![]);
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::WithdrawFunds {
+                denom: BONDED_DENOM.to_string(),
+                amount: Uint128::from(4_100u128),
+                recipient: None,
+            },
+        );
+        assert_eq!(balance(&_instance, &vault), 0);
+        assert_eq!(balance(&_instance, OWNER), SUPPLY - 10_000 + 4_100);
+    }
+
+    #[test]
+    fn test_only_owner_and_manager() {
+        let mut _instance = mock_instantiate();
+
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MANAGER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Delegate {
+                    validator: "validator1".to_string(),
+                    amount: Uint128::from(1_000u128),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(MANAGER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::ChangeOwner {
+                    new_owner: "new_owner".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("new_owner"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Delegate {
+                    validator: "validator1".to_string(),
+                    amount: Uint128::from(1_000u128),
+                },
+                &[],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_controller_withdraws_until_expiry() {
+        let mut _instance = mock_instantiate();
+        let vault = _instance.c_addr.to_string();
+        let height = _instance.app.block_info().height;
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::SetController {
+                address: "lender".to_string(),
+                actions_scope: vec![Action::WithdrawFunds],
+                expiration_date: Expiration::AtHeight(height + 10),
+                percentage_stake_to_undelegate_at_liquidation: Decimal::percent(100),
+            },
+        );
+
+        let withdraw = ExecuteMsg::WithdrawFunds {
+            denom: BONDED_DENOM.to_string(),
+            amount: Uint128::from(1_000u128),
+            recipient: None,
+        };
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("lender"),
+                _instance.c_addr.clone(),
+                &withdraw,
+                &[],
+            )
+            .unwrap();
+        assert_eq!(balance(&_instance, "lender"), 1_000);
+        assert_eq!(balance(&_instance, &vault), 9_000);
+
+        _instance.app.update_block(|block| block.height += 10);
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("lender"),
+                _instance.c_addr.clone(),
+                &withdraw,
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::ActionNotAllowed {
+                action: Action::WithdrawFunds
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn test_loan_liquidation_flow() {
+        let mut _instance = mock_instantiate();
+        let vault = _instance.c_addr.to_string();
+        _instance
+            .app
+            .send_tokens(
+                Addr::unchecked(OWNER),
+                Addr::unchecked("lender"),
+                &coins(5_000, BONDED_DENOM),
+            )
+            .unwrap();
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(10_000u128),
+            },
+        );
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::CreateDeal {
+                terms: DealTerms::Loan {
+                    amount: coin(5_000, BONDED_DENOM),
+                    repayment_amount: Uint128::from(5_500u128),
+                    duration: 86_400,
+                    percentage_stake_to_undelegate_at_liquidation: Decimal::percent(60),
+                },
+            },
+        );
+        let owner_balance = balance(&_instance, OWNER);
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("lender"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::AcceptDeal {},
+                &coins(5_000, BONDED_DENOM),
+            )
+            .unwrap();
+        assert_eq!(balance(&_instance, OWNER), owner_balance + 5_000);
+
+        // the loan isn't repaid in time, 60% of the stake is unbonded for the lender
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(86_400));
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("lender"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Liquidate {},
+                &[],
+            )
+            .unwrap();
+        let res: DelegationsResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::Delegations {})
+            .unwrap();
+        assert_eq!(res.delegations[0].amount, coin(4_000, BONDED_DENOM));
+
+        // the owner can't take the unbonded stake back
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::WithdrawFunds {
+                    denom: BONDED_DENOM.to_string(),
+                    amount: Uint128::from(6_000u128),
+                    recipient: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::ActiveLoan {}.to_string()
+        );
+
+        // complete the unbonding by hand, the test staking module keeps undelegated tokens
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(UNBONDING_PERIOD));
+        _instance
+            .app
+            .send_tokens(
+                Addr::unchecked(STAKING_MODULE),
+                _instance.c_addr.clone(),
+                &coins(6_000, BONDED_DENOM),
+            )
+            .unwrap();
+        let lender_balance = balance(&_instance, "lender");
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("lender"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Liquidate {},
+                &[],
+            )
+            .unwrap();
+        assert_eq!(balance(&_instance, "lender"), lender_balance + 6_000);
+
+        let res: DealResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&vault, &QueryMsg::Deal {})
+            .unwrap();
+        assert_eq!(res.deal, None);
+    }
+
+    #[test]
+    fn test_staking_rewards_deal_flow() {
+        let mut _instance = mock_instantiate();
+        _instance
+            .app
+            .send_tokens(
+                Addr::unchecked(OWNER),
+                Addr::unchecked("investor"),
+                &coins(1_000, BONDED_DENOM),
+            )
+            .unwrap();
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(5_000u128),
+            },
+        );
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::CreateDeal {
+                terms: DealTerms::StakingRewards {
+                    amount: coin(1_000, BONDED_DENOM),
+                    duration: 86_400,
+                    reward_cap: Uint128::from(300u128),
+                },
+            },
+        );
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("investor"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::AcceptDeal {},
+                &coins(1_000, BONDED_DENOM),
+            )
+            .unwrap();
+
+        // the rewards withdrawn by a delegation change go to the investor, up to the cap
+        _instance.app.update_block(|block| block.height += 4);
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(1_000u128),
+            },
+        );
+        assert_eq!(balance(&_instance, "investor"), 200);
+        _instance.app.update_block(|block| block.height += 4);
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(1_000u128),
+            },
+        );
+        assert_eq!(balance(&_instance, "investor"), 300);
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("investor"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::TerminateDeal {},
+                &[],
+            )
+            .unwrap();
+        let res: DealResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&_instance.c_addr, &QueryMsg::Deal {})
+            .unwrap();
+        assert_eq!(res.deal, None);
+    }
+
+    #[test]
+    fn test_withdraw_funds_during_staking_rewards_deal() {
+        let mut _instance = mock_instantiate();
+        _instance
+            .app
+            .send_tokens(
+                Addr::unchecked(OWNER),
+                Addr::unchecked("investor"),
+                &coins(1_000, BONDED_DENOM),
+            )
+            .unwrap();
+
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(5_000u128),
+            },
+        );
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::CreateDeal {
+                terms: DealTerms::StakingRewards {
+                    amount: coin(1_000, BONDED_DENOM),
+                    duration: 86_400,
+                    reward_cap: Uint128::from(1_000u128),
+                },
+            },
+        );
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("investor"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::AcceptDeal {},
+                &coins(1_000, BONDED_DENOM),
+            )
+            .unwrap();
+
+        // the rewards withdrawn by the delegation change are paid before
+        // the owner could move them out of the vault
+        _instance.app.update_block(|block| block.height += 4);
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(1_000u128),
+            },
+        );
+        assert_eq!(balance(&_instance, "investor"), 200);
+        assert_eq!(balance(&_instance, _instance.c_addr.as_str()), 4_000);
+
+        // the bonded denom can't be withdrawn until the deal ends
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::WithdrawFunds {
+                    denom: BONDED_DENOM.to_string(),
+                    amount: Uint128::from(4_000u128),
+                    recipient: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::ActiveDeal {}.to_string()
+        );
+
+        _instance
+            .app
+            .update_block(|block| block.time = block.time.plus_seconds(86_400));
+        let owner_balance = balance(&_instance, OWNER);
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::WithdrawFunds {
+                denom: BONDED_DENOM.to_string(),
+                amount: Uint128::from(4_000u128),
+                recipient: None,
+            },
+        );
+        assert_eq!(balance(&_instance, OWNER), owner_balance + 4_000);
+    }
+
+    #[test]
+    fn test_vote_only_by_owner() {
+        let mut _instance = mock_instantiate();
+
+        // even a controller granted the vote action can't vote
+        execute_as_owner(
+            &mut _instance,
+            ExecuteMsg::SetController {
+                address: "controller".to_string(),
+                actions_scope: vec![Action::Vote],
+                expiration_date: Expiration::Never {},
+                percentage_stake_to_undelegate_at_liquidation: Decimal::zero(),
+            },
+        );
+        let votes = [
+            ExecuteMsg::Vote {
+                proposal_id: 1,
+                vote: VoteOption::Yes,
+            },
+            ExecuteMsg::VoteWeighted {
+                proposal_id: 1,
+                options: vec![
+                    WeightedVoteOption {
+                        option: VoteOption::Yes,
+                        weight: Decimal::percent(70),
+                    },
+                    WeightedVoteOption {
+                        option: VoteOption::No,
+                        weight: Decimal::percent(30),
+                    },
+                ],
+            },
+        ];
+        for sender in ["controller", MANAGER] {
+            for msg in votes.iter() {
+                let _err = _instance
+                    .app
+                    .execute_contract(Addr::unchecked(sender), _instance.c_addr.clone(), msg, &[])
+                    .unwrap_err();
+                assert_eq!(
+                    _err.downcast::<ContractError>().unwrap().to_string(),
+                    ContractError::Unauthorized {}.to_string()
+                );
+            }
+        }
+
+        // cw-multi-test has no gov module, the vote of the owner gets past the
+        // vault and is only refused by the router
+        for msg in votes.iter() {
+            let _err = _instance
+                .app
+                .execute_contract(Addr::unchecked(OWNER), _instance.c_addr.clone(), msg, &[])
+                .unwrap_err();
+            assert!(_err.downcast_ref::<ContractError>().is_none());
+            assert!(_err.root_cause().to_string().starts_with("Cannot execute"));
+        }
+
+        // nothing is recorded when the vote fails
+        let res: VotesResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &_instance.c_addr,
+                &QueryMsg::Votes {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(res.votes.is_empty());
+    }
+}
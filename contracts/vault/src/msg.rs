@@ -0,0 +1,148 @@
+use cosmwasm_std::{Coin, Decimal, Delegation, Uint128, VoteOption};
+use cw20::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{
+    Action, AutoCompound, Deal, DealTerms, UnbondingEntry, Vote, WeightedVoteOption,
+};
+
+// The vaults manager instantiates the vault, it is stored as vault_manager_address
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub owner: String,
+    pub unbonding_period: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // Amounts are in the bonded denom of the chain
+    Delegate {
+        validator: String,
+        amount: Uint128,
+    },
+    Undelegate {
+        validator: String,
+        amount: Uint128,
+    },
+    Redelegate {
+        src_validator: String,
+        dst_validator: String,
+        amount: Uint128,
+    },
+    // Withdraw the rewards of every delegation of the vault
+    ClaimRewards {},
+    // Send funds held by the vault to recipient, defaults to the caller
+    WithdrawFunds {
+        denom: String,
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    // Owner only, grant actions to a controller until expiration_date.
+    // Refused while another controller is in effect
+    SetController {
+        address: String,
+        actions_scope: Vec<Action>,
+        expiration_date: Expiration,
+        percentage_stake_to_undelegate_at_liquidation: Decimal,
+    },
+    // The controller can give up its rights at any time,
+    // the owner only once they are no longer in effect
+    RevokeController {},
+    // Owner only, offer a deal on the vault, one at a time
+    CreateDeal {
+        terms: DealTerms,
+    },
+    // Owner only, withdraw a deal that hasn't been accepted
+    CancelDeal {},
+    // Send the deal amount, the counterparty becomes the vault controller
+    AcceptDeal {},
+    // Send the repayment_amount of the active loan to the lender
+    RepayLoan {},
+    // Lender only, once the loan deadline has passed. Undelegates the liquidation
+    // share of the stake, a second call sends it to the lender once unbonded
+    Liquidate {},
+    // Close a staking rewards deal. Before its end only the owner can terminate
+    // it, refunding the termination_amount of the Deal query to the counterparty
+    TerminateDeal {},
+    // Owner only, None turns auto compounding off
+    SetAutoCompound {
+        settings: Option<AutoCompound>,
+    },
+    // Anyone, claim the rewards and delegate them to the auto compound
+    // validators, the caller receives the keeper_fee
+    Compound {},
+    // Owner only, vote on a governance proposal with the vault stake
+    Vote {
+        proposal_id: u64,
+        vote: VoteOption,
+    },
+    // Owner only, the weights must add up to 1. Needs a chain
+    // supporting MsgVoteWeighted (cosmos-sdk 0.43 or later)
+    VoteWeighted {
+        proposal_id: u64,
+        options: Vec<WeightedVoteOption>,
+    },
+    // Vaults manager only
+    ChangeOwner {
+        new_owner: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Preferences {},
+    Delegations {},
+    PendingRewards {},
+    // The undelegations that haven't completed yet
+    UnbondingEntries {},
+    Deal {},
+    AutoCompound {},
+    // Paginated by proposal id
+    Votes {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DelegationsResponse {
+    pub delegations: Vec<Delegation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ValidatorRewards {
+    pub validator: String,
+    pub rewards: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRewardsResponse {
+    pub rewards: Vec<ValidatorRewards>,
+    // The rewards of all the delegations added up per denom
+    pub total: Vec<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DealResponse {
+    pub deal: Option<Deal>,
+    // The refund owed to end an active staking rewards deal early
+    pub termination_amount: Option<Coin>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotesResponse {
+    pub votes: Vec<Vote>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoCompoundResponse {
+    pub settings: Option<AutoCompound>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingEntriesResponse {
+    pub entries: Vec<UnbondingEntry>,
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+
+use cosmwasm_std::{Addr, BlockInfo, Coin, Decimal, Timestamp, Uint128, VoteOption};
+use cw20::Expiration;
+use cw_storage_plus::{Item, Map};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// The actions a role can be allowed to perform on the vault
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Delegate,
+    Undelegate,
+    Redelegate,
+    ClaimRewards,
+    WithdrawFunds,
+    // Only granted to the owner
+    Vote,
+}
+
+impl Action {
+    pub fn all() -> Vec<Action> {
+        vec![
+            Action::Delegate,
+            Action::Undelegate,
+            Action::Redelegate,
+            Action::ClaimRewards,
+            Action::WithdrawFunds,
+            Action::Vote,
+        ]
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let action = match self {
+            Action::Delegate => "delegate",
+            Action::Undelegate => "undelegate",
+            Action::Redelegate => "redelegate",
+            Action::ClaimRewards => "claim_rewards",
+            Action::WithdrawFunds => "withdraw_funds",
+            Action::Vote => "vote",
+        };
+        write!(f, "{}", action)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Owner {
+    pub address: Addr,
+    pub actions_scope: Vec<Action>,
+    pub is_active: bool,
+}
+
+// A third party granted some actions on the vault by the owner until expiration_date
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Controller {
+    pub address: Addr,
+    pub actions_scope: Vec<Action>,
+    pub expiration_date: Expiration,
+    // Share of the delegations undelegated for the controller on liquidation
+    pub percentage_stake_to_undelegate_at_liquidation: Decimal,
+    pub is_active: bool,
+}
+
+impl Controller {
+    // An inactive or expired controller has no rights left on the vault
+    pub fn is_in_effect(&self, block: &BlockInfo) -> bool {
+        self.is_active && !self.expiration_date.is_expired(block)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultPreferences {
+    // Only the vaults manager can update the owner's address
+    pub vault_manager_address: Addr,
+    // The chain unbonding period in seconds, used to date the unbonding entries
+    pub unbonding_period: u64,
+    pub owner: Owner,
+    pub controller: Option<Controller>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UnbondingEntry {
+    pub validator: String,
+    pub amount: Coin,
+    pub completion_time: Timestamp,
+}
+
+// The terms offered by the owner with CreateDeal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DealTerms {
+    // The counterparty lends amount to the owner against the vault stake, to be
+    // repaid with repayment_amount of the same denom within duration seconds
+    Loan {
+        amount: Coin,
+        repayment_amount: Uint128,
+        duration: u64,
+        percentage_stake_to_undelegate_at_liquidation: Decimal,
+    },
+    // The counterparty pays amount to the owner upfront and receives the
+    // staking rewards of the vault for duration seconds or until it has
+    // received reward_cap of the bonded denom
+    StakingRewards {
+        amount: Coin,
+        duration: u64,
+        reward_cap: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Deal {
+    pub terms: DealTerms,
+    // Set when the deal is accepted
+    pub counterparty: Option<Addr>,
+    pub deadline: Option<Timestamp>,
+    // Set when the counterparty liquidates an overdue loan
+    pub liquidation: Option<Liquidation>,
+    // The rewards sent to the counterparty of a staking rewards deal
+    pub rewards_paid: Uint128,
+}
+
+// The stake undelegated for the lender, sent to them once unbonded
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Liquidation {
+    pub amount: Coin,
+    pub completion_time: Timestamp,
+}
+
+// The rewards of the vault are delegated to validators by anyone calling
+// Compound once they reach min_threshold, keeper_fee of them is paid to the caller
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AutoCompound {
+    pub validators: Vec<String>,
+    pub min_threshold: Uint128,
+    pub keeper_fee: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct WeightedVoteOption {
+    pub option: VoteOption,
+    pub weight: Decimal,
+}
+
+// The last vote cast by the vault on a proposal, a plain vote
+// is recorded as its option with a weight of 1
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Vote {
+    pub proposal_id: u64,
+    pub options: Vec<WeightedVoteOption>,
+    pub voted_at: Timestamp,
+}
+
+pub const VAULT_PREFERENCES: Item<VaultPreferences> = Item::new("VAULT_PREFERENCES");
+
+// This stores the undelegations issued by the vault, the chain doesn't expose
+// them to contracts. Entries are dropped once their completion_time is past
+pub const UNBONDING_ENTRIES: Item<Vec<UnbondingEntry>> = Item::new("UNBONDING_ENTRIES");
+
+// The deal offered or running on the vault, there is at most one at a time
+pub const DEAL: Item<Deal> = Item::new("DEAL");
+
+// The auto compound settings of the owner, removed when the vault changes hands
+pub const AUTO_COMPOUND: Item<AutoCompound> = Item::new("AUTO_COMPOUND");
+
+// The votes cast by the vault keyed by proposal id
+pub const VOTES: Map<u64, Vote> = Map::new("VOTES");
+
+// Limits for the custom range query
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
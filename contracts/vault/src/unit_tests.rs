@@ -0,0 +1,1225 @@
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{
+        coin, coins, from_binary, Addr, BankMsg, CosmosMsg, Decimal, DistributionMsg, Empty, Env,
+        FullDelegation, GovMsg, MemoryStorage, OwnedDeps, StakingMsg, Uint128, Validator,
+        VoteOption,
+    };
+    use cw20::Expiration;
+
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        AutoCompoundResponse, DealResponse, ExecuteMsg, InstantiateMsg, PendingRewardsResponse,
+        QueryMsg, UnbondingEntriesResponse, ValidatorRewards, VotesResponse,
+    };
+    use crate::state::{
+        Action, AutoCompound, Controller, DealTerms, Liquidation, Owner, UnbondingEntry,
+        VaultPreferences, Vote, WeightedVoteOption,
+    };
+    use crate::ContractError;
+
+    const MANAGER: &str = "vaults_manager";
+    const OWNER: &str = "owner";
+    const BONDED_DENOM: &str = "ustake";
+    const UNBONDING_PERIOD: u64 = 1_209_600;
+
+    struct InstantiationResponse {
+        deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>, Empty>,
+        env: Env,
+    }
+
+    fn validator(address: &str) -> Validator {
+        Validator {
+            address: address.to_string(),
+            commission: Decimal::percent(5),
+            max_commission: Decimal::percent(10),
+            max_change_rate: Decimal::percent(1),
+        }
+    }
+
+    fn delegation(validator: &str, amount: u128, rewards: u128) -> FullDelegation {
+        FullDelegation {
+            delegator: Addr::unchecked(MOCK_CONTRACT_ADDR),
+            validator: validator.to_string(),
+            amount: coin(amount, BONDED_DENOM),
+            can_redelegate: coin(amount, BONDED_DENOM),
+            accumulated_rewards: coins(rewards, BONDED_DENOM),
+        }
+    }
+
+    // This function instantiate the contract and returns reusable components
+    fn proper_initialization() -> InstantiationResponse {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        deps.querier.update_staking(
+            BONDED_DENOM,
+            &[validator("validator1"), validator("validator2")],
+            &[
+                delegation("validator1", 1_000, 10),
+                delegation("validator2", 2_000, 25),
+            ],
+        );
+
+        let msg = InstantiateMsg {
+            owner: OWNER.to_string(),
+            unbonding_period: UNBONDING_PERIOD,
+        };
+        let info = mock_info(MANAGER, &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // query and verify state
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Preferences {}).unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert_eq!(
+            preferences,
+            VaultPreferences {
+                vault_manager_address: Addr::unchecked(MANAGER),
+                unbonding_period: UNBONDING_PERIOD,
+                owner: Owner {
+                    address: Addr::unchecked(OWNER),
+                    actions_scope: Action::all(),
+                    is_active: true,
+                },
+                controller: None,
+            }
+        );
+
+        InstantiationResponse { deps, env }
+    }
+
+    #[test]
+    fn test_staking_actions() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(OWNER, &[]);
+
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(500u128),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: coin(500, BONDED_DENOM),
+            })
+        );
+
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            ExecuteMsg::Redelegate {
+                src_validator: "validator1".to_string(),
+                dst_validator: "validator2".to_string(),
+                amount: Uint128::from(200u128),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Redelegate {
+                src_validator: "validator1".to_string(),
+                dst_validator: "validator2".to_string(),
+                amount: coin(200, BONDED_DENOM),
+            })
+        );
+
+        // rewards are withdrawn from every delegation
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages
+                .iter()
+                .map(|m| m.msg.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: "validator1".to_string(),
+                }),
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: "validator2".to_string(),
+                }),
+            ]
+        );
+
+        // withdrawn funds default to the caller
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            ExecuteMsg::WithdrawFunds {
+                denom: BONDED_DENOM.to_string(),
+                amount: Uint128::from(35u128),
+                recipient: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OWNER.to_string(),
+                amount: coins(35, BONDED_DENOM),
+            })
+        );
+
+        // no one else can act on the vault
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(500u128),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_undelegate_records_unbonding_entries() {
+        let mut _instance = proper_initialization();
+        let info = mock_info(OWNER, &[]);
+
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info.clone(),
+            ExecuteMsg::Undelegate {
+                validator: "validator2".to_string(),
+                amount: Uint128::from(300u128),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Staking(StakingMsg::Undelegate {
+                validator: "validator2".to_string(),
+                amount: coin(300, BONDED_DENOM),
+            })
+        );
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::UnbondingEntries {},
+        )
+        .unwrap();
+        let res: UnbondingEntriesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.entries,
+            vec![UnbondingEntry {
+                validator: "validator2".to_string(),
+                amount: coin(300, BONDED_DENOM),
+                completion_time: _instance.env.block.time.plus_seconds(UNBONDING_PERIOD),
+            }]
+        );
+
+        // completed entries are no longer returned
+        let mut env = _instance.env.clone();
+        env.block.time = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let res = query(_instance.deps.as_ref(), env, QueryMsg::UnbondingEntries {}).unwrap();
+        let res: UnbondingEntriesResponse = from_binary(&res).unwrap();
+        assert_eq!(res.entries, vec![]);
+
+        // zero amounts are refused
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            ExecuteMsg::Undelegate {
+                validator: "validator2".to_string(),
+                amount: Uint128::zero(),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ZeroAmount {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_pending_rewards() {
+        let _instance = proper_initialization();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::PendingRewards {},
+        )
+        .unwrap();
+        let res: PendingRewardsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            PendingRewardsResponse {
+                rewards: vec![
+                    ValidatorRewards {
+                        validator: "validator1".to_string(),
+                        rewards: coins(10, BONDED_DENOM),
+                    },
+                    ValidatorRewards {
+                        validator: "validator2".to_string(),
+                        rewards: coins(25, BONDED_DENOM),
+                    },
+                ],
+                total: coins(35, BONDED_DENOM),
+            }
+        );
+    }
+
+    #[test]
+    fn test_change_owner() {
+        let mut _instance = proper_initialization();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(Expiration::Never {}),
+        )
+        .unwrap();
+
+        // only the manager can change the owner, not even the owner
+        let msg = ExecuteMsg::ChangeOwner {
+            new_owner: "new_owner".to_string(),
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(MANAGER, &[]),
+            msg,
+        )
+        .unwrap();
+
+        // the previous owner lost its rights
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap();
+
+        // the controller set by the previous owner is removed with the vault
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Preferences {},
+        )
+        .unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert_eq!(preferences.controller, None);
+    }
+
+    fn set_controller_msg(expiration_date: Expiration) -> ExecuteMsg {
+        ExecuteMsg::SetController {
+            address: "lender".to_string(),
+            actions_scope: vec![Action::ClaimRewards, Action::WithdrawFunds],
+            expiration_date,
+            percentage_stake_to_undelegate_at_liquidation: Decimal::percent(50),
+        }
+    }
+
+    #[test]
+    fn test_controller_permissions() {
+        let mut _instance = proper_initialization();
+        let expiration_date = Expiration::AtHeight(_instance.env.block.height + 100);
+
+        // only the owner sets the controller
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            set_controller_msg(expiration_date),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // an expired controller is refused
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(Expiration::AtHeight(_instance.env.block.height)),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidController {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(expiration_date),
+        )
+        .unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Preferences {},
+        )
+        .unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert_eq!(
+            preferences.controller,
+            Some(Controller {
+                address: Addr::unchecked("lender"),
+                actions_scope: vec![Action::ClaimRewards, Action::WithdrawFunds],
+                expiration_date,
+                percentage_stake_to_undelegate_at_liquidation: Decimal::percent(50),
+                is_active: true,
+            })
+        );
+
+        // the controller is limited to its actions_scope
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap();
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::Delegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::from(500u128),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ActionNotAllowed {
+                action: Action::Delegate,
+            } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the owner can neither replace nor revoke the controller in effect
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(expiration_date),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ControllerInEffect {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::RevokeController {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // once expired the controller loses its rights and the owner can revoke it
+        let mut env = _instance.env.clone();
+        env.block.height += 100;
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ActionNotAllowed {
+                action: Action::ClaimRewards,
+            } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        execute(
+            _instance.deps.as_mut(),
+            env,
+            mock_info(OWNER, &[]),
+            ExecuteMsg::RevokeController {},
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_controller_gives_up_rights() {
+        let mut _instance = proper_initialization();
+        let expiration_date = Expiration::AtHeight(_instance.env.block.height + 100);
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(expiration_date),
+        )
+        .unwrap();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::RevokeController {},
+        )
+        .unwrap();
+
+        // the owner can set a new controller right away
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(expiration_date),
+        )
+        .unwrap();
+    }
+
+    fn loan_terms() -> DealTerms {
+        DealTerms::Loan {
+            amount: coin(500, "uusd"),
+            repayment_amount: Uint128::new(550),
+            duration: 86_400,
+            percentage_stake_to_undelegate_at_liquidation: Decimal::percent(20),
+        }
+    }
+
+    // Create the loan deal as the owner and accept it as the lender
+    fn accept_loan(_instance: &mut InstantiationResponse) {
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::CreateDeal {
+                terms: loan_terms(),
+            },
+        )
+        .unwrap();
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &coins(500, "uusd")),
+            ExecuteMsg::AcceptDeal {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: OWNER.to_string(),
+                amount: coins(500, "uusd"),
+            })
+        );
+    }
+
+    #[test]
+    fn test_loan_repayment() {
+        let mut _instance = proper_initialization();
+
+        // the loan amount must be sent exactly
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::CreateDeal {
+                terms: loan_terms(),
+            },
+        )
+        .unwrap();
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &coins(400, "uusd")),
+            ExecuteMsg::AcceptDeal {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::IncorrectFunds { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::CancelDeal {},
+        )
+        .unwrap();
+
+        accept_loan(&mut _instance);
+
+        // the lender controls the rewards and the owner can't unbond the collateral
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Preferences {},
+        )
+        .unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        let controller = preferences.controller.unwrap();
+        assert_eq!(controller.address, Addr::unchecked("lender"));
+        assert_eq!(controller.actions_scope, vec![Action::ClaimRewards]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Undelegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ActiveLoan {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::CancelDeal {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::DealAlreadyAccepted {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &coins(550, "uusd")),
+            ExecuteMsg::RepayLoan {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "lender".to_string(),
+                amount: coins(550, "uusd"),
+            })
+        );
+
+        // the deal and the controller are gone
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Deal {},
+        )
+        .unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(deal.deal, None);
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Preferences {},
+        )
+        .unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert_eq!(preferences.controller, None);
+    }
+
+    #[test]
+    fn test_loan_liquidation() {
+        let mut _instance = proper_initialization();
+        accept_loan(&mut _instance);
+
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::Liquidate {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::LoanNotDue {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the percentage comes from the loan terms, not from the lender's controller
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::RevokeController {},
+        )
+        .unwrap();
+
+        // 20% of every delegation is undelegated once the loan is overdue
+        let mut env = _instance.env.clone();
+        env.block.time = env.block.time.plus_seconds(86_400);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Liquidate {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let res = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::Liquidate {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Staking(StakingMsg::Undelegate {
+                    validator: "validator1".to_string(),
+                    amount: coin(200, BONDED_DENOM),
+                }),
+                CosmosMsg::Staking(StakingMsg::Undelegate {
+                    validator: "validator2".to_string(),
+                    amount: coin(400, BONDED_DENOM),
+                }),
+            ]
+        );
+        let completion_time = env.block.time.plus_seconds(UNBONDING_PERIOD);
+        let res = query(_instance.deps.as_ref(), env.clone(), QueryMsg::Deal {}).unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            deal.deal.unwrap().liquidation,
+            Some(Liquidation {
+                amount: coin(600, BONDED_DENOM),
+                completion_time,
+            })
+        );
+
+        // the loan can't be repaid anymore and the stake is paid out once unbonded
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &coins(550, "uusd")),
+            ExecuteMsg::RepayLoan {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::NoActiveLoan {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::Liquidate {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::LiquidationNotComplete {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        env.block.time = completion_time;
+        _instance
+            .deps
+            .querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(600, BONDED_DENOM));
+        let res = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info("lender", &[]),
+            ExecuteMsg::Liquidate {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "lender".to_string(),
+                amount: coins(600, BONDED_DENOM),
+            })
+        );
+        let res = query(_instance.deps.as_ref(), env, QueryMsg::Deal {}).unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(deal.deal, None);
+    }
+
+    // Offer the vault rewards for 1000uusd upfront and accept it as the investor
+    fn accept_rewards_deal(_instance: &mut InstantiationResponse) {
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::CreateDeal {
+                terms: DealTerms::StakingRewards {
+                    amount: coin(1_000, "uusd"),
+                    duration: 100_000,
+                    reward_cap: Uint128::new(50),
+                },
+            },
+        )
+        .unwrap();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("investor", &coins(1_000, "uusd")),
+            ExecuteMsg::AcceptDeal {},
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_staking_rewards_deal() {
+        let mut _instance = proper_initialization();
+        accept_rewards_deal(&mut _instance);
+
+        // the stake keeps earning for the investor
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Undelegate {
+                validator: "validator1".to_string(),
+                amount: Uint128::new(100),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ActiveDeal {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the claimed rewards are sent to the investor
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("investor", &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap();
+        assert_eq!(res.messages.len(), 3);
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "investor".to_string(),
+                amount: coins(35, BONDED_DENOM),
+            })
+        );
+
+        // halfway through with 30% of the cap left, 30% of the upfront amount is owed
+        let mut env = _instance.env.clone();
+        env.block.time = env.block.time.plus_seconds(50_000);
+        let res = query(_instance.deps.as_ref(), env.clone(), QueryMsg::Deal {}).unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(deal.deal.unwrap().rewards_paid, Uint128::new(35));
+        assert_eq!(deal.termination_amount, Some(coin(300, "uusd")));
+
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info("investor", &[]),
+            ExecuteMsg::TerminateDeal {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::DealNotEnded {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &coins(200, "uusd")),
+            ExecuteMsg::TerminateDeal {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::IncorrectFunds { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(
+            _instance.deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &coins(350, "uusd")),
+            ExecuteMsg::TerminateDeal {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "investor".to_string(),
+                    amount: coins(300, "uusd"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: OWNER.to_string(),
+                    amount: coins(50, "uusd"),
+                }),
+            ]
+        );
+        let res = query(_instance.deps.as_ref(), env, QueryMsg::Preferences {}).unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert_eq!(preferences.controller, None);
+    }
+
+    #[test]
+    fn test_staking_rewards_deal_reward_cap() {
+        let mut _instance = proper_initialization();
+        accept_rewards_deal(&mut _instance);
+
+        let claim = |_instance: &mut InstantiationResponse| {
+            execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                mock_info("investor", &[]),
+                ExecuteMsg::ClaimRewards {},
+            )
+            .unwrap()
+        };
+        claim(&mut _instance);
+
+        // only what is left under the cap is paid, then the deal has ended
+        let res = claim(&mut _instance);
+        assert_eq!(
+            res.messages[2].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "investor".to_string(),
+                amount: coins(15, BONDED_DENOM),
+            })
+        );
+        let res = claim(&mut _instance);
+        assert_eq!(res.messages.len(), 2);
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Deal {},
+        )
+        .unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(deal.termination_amount, None);
+
+        // either party can then close it
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("investor", &[]),
+            ExecuteMsg::TerminateDeal {},
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Deal {},
+        )
+        .unwrap();
+        let deal: DealResponse = from_binary(&res).unwrap();
+        assert_eq!(deal.deal, None);
+    }
+
+    fn auto_compound_msg(min_threshold: u128, keeper_fee: Decimal) -> ExecuteMsg {
+        ExecuteMsg::SetAutoCompound {
+            settings: Some(AutoCompound {
+                validators: vec!["validator1".to_string(), "validator2".to_string()],
+                min_threshold: Uint128::new(min_threshold),
+                keeper_fee,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_auto_compound() {
+        let mut _instance = proper_initialization();
+        let compound = |_instance: &mut InstantiationResponse| {
+            execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                mock_info("keeper", &[]),
+                ExecuteMsg::Compound {},
+            )
+        };
+
+        match compound(&mut _instance).unwrap_err() {
+            ContractError::AutoCompoundDisabled {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("keeper", &[]),
+            auto_compound_msg(20, Decimal::percent(5)),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            auto_compound_msg(20, Decimal::percent(10)),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidAutoCompound { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // 35ustake of rewards are pending
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            auto_compound_msg(50, Decimal::percent(5)),
+        )
+        .unwrap();
+        match compound(&mut _instance).unwrap_err() {
+            ContractError::BelowThreshold { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            auto_compound_msg(20, Decimal::percent(5)),
+        )
+        .unwrap();
+        let res = compound(&mut _instance).unwrap();
+        assert_eq!(
+            res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: "validator1".to_string(),
+                }),
+                CosmosMsg::Distribution(DistributionMsg::WithdrawDelegatorReward {
+                    validator: "validator2".to_string(),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "keeper".to_string(),
+                    amount: coins(1, BONDED_DENOM),
+                }),
+                CosmosMsg::Staking(StakingMsg::Delegate {
+                    validator: "validator1".to_string(),
+                    amount: coin(17, BONDED_DENOM),
+                }),
+                CosmosMsg::Staking(StakingMsg::Delegate {
+                    validator: "validator2".to_string(),
+                    amount: coin(17, BONDED_DENOM),
+                }),
+            ]
+        );
+
+        // the rewards of a staking rewards deal aren't compounded
+        accept_rewards_deal(&mut _instance);
+        match compound(&mut _instance).unwrap_err() {
+            ContractError::ActiveDeal {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // the settings of the previous owner are dropped with the vault
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(MANAGER, &[]),
+            ExecuteMsg::ChangeOwner {
+                new_owner: "new_owner".to_string(),
+            },
+        )
+        .unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::AutoCompound {},
+        )
+        .unwrap();
+        let res: AutoCompoundResponse = from_binary(&res).unwrap();
+        assert_eq!(res.settings, None);
+
+        // while the controller of the accepted deal stays in place
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Preferences {},
+        )
+        .unwrap();
+        let preferences: VaultPreferences = from_binary(&res).unwrap();
+        assert!(preferences.controller.is_some());
+    }
+
+    #[test]
+    fn test_governance_votes() {
+        let mut _instance = proper_initialization();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            set_controller_msg(Expiration::Never {}),
+        )
+        .unwrap();
+
+        // the controller can't vote with the vault stake
+        let vote = ExecuteMsg::Vote {
+            proposal_id: 1,
+            vote: VoteOption::Yes,
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("lender", &[]),
+            vote.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ActionNotAllowed {
+                action: Action::Vote,
+            } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            vote,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Gov(GovMsg::Vote {
+                proposal_id: 1,
+                vote: VoteOption::Yes,
+            })
+        );
+
+        let weighted_options = |yes: u64, no: u64| {
+            vec![
+                WeightedVoteOption {
+                    option: VoteOption::Yes,
+                    weight: Decimal::percent(yes),
+                },
+                WeightedVoteOption {
+                    option: VoteOption::No,
+                    weight: Decimal::percent(no),
+                },
+            ]
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::VoteWeighted {
+                proposal_id: 2,
+                options: weighted_options(70, 20),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidVote {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // weighted votes are sent as a protobuf encoded MsgVoteWeighted
+        let res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::VoteWeighted {
+                proposal_id: 2,
+                options: weighted_options(70, 30),
+            },
+        )
+        .unwrap();
+        let mut value = vec![0x08, 2, 0x12, MOCK_CONTRACT_ADDR.len() as u8];
+        value.extend_from_slice(MOCK_CONTRACT_ADDR.as_bytes());
+        value.extend_from_slice(&[0x1a, 22, 0x08, 1, 0x12, 18]);
+        value.extend_from_slice(b"700000000000000000");
+        value.extend_from_slice(&[0x1a, 22, 0x08, 3, 0x12, 18]);
+        value.extend_from_slice(b"300000000000000000");
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Stargate {
+                type_url: "/cosmos.gov.v1beta1.MsgVoteWeighted".to_string(),
+                value: value.into(),
+            }
+        );
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Votes {
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: VotesResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.votes,
+            vec![Vote {
+                proposal_id: 2,
+                options: weighted_options(70, 30),
+                voted_at: _instance.env.block.time,
+            }]
+        );
+    }
+}
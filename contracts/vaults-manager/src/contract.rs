@@ -0,0 +1,1072 @@
+use crate::error::ContractError;
+use crate::msg::{
+    AllNftInfoResponse, Approval, ApprovalResponse, ApprovalsResponse, ContractInfoResponse,
+    Cw721ReceiveMsg, ExecuteMsg, InstantiateMsg, ListingResponse, ListingsResponse,
+    NftInfoResponse, NumTokensResponse, OperatorsResponse, OwnerOfResponse, QueryMsg, SudoMsg,
+    TokensResponse, VaultMetadata, VaultResponse, VaultsResponse,
+};
+use crate::state::{
+    listings, vaults, Config, Listing, VaultInfo, DEFAULT_LIMIT, INIT_CONFIG, MAX_LIMIT, OPERATORS,
+    PENDING_MINTS, REPLY_SEQ, TOKEN_APPROVALS, VAULT_SEQ,
+};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    coin, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut, Env,
+    MessageInfo, Order, Reply, ReplyOn, Response, StdError, StdResult, Storage, SubMsg, WasmMsg,
+};
+use cw_storage_plus::Bound;
+use cw_utils::{parse_reply_instantiate_data, Expiration};
+use vault::msg::{
+    DealResponse, DelegationsResponse, ExecuteMsg as VaultExecuteMsg,
+    InstantiateMsg as VaultInstantiateMsg, QueryMsg as VaultQueryMsg,
+};
+use vault::state::{Deal, DealTerms};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:vaults-manager";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// cw721 contract info of the vaults
+const NFT_NAME: &str = "Sudostake Vaults";
+const NFT_SYMBOL: &str = "VAULT";
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    //Store the contract name and version
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+
+    if msg.vault_creation_fee.amount.is_zero() {
+        return Err(ContractError::InvalidVaultCreationFee {});
+    }
+
+    let marketplace_fee = msg.marketplace_fee.unwrap_or_default();
+    if marketplace_fee >= Decimal::one() {
+        return Err(ContractError::InvalidMarketplaceFee {});
+    }
+    let treasury_addr = match msg.treasury_addr {
+        Some(treasury_addr) => deps.api.addr_validate(&treasury_addr)?,
+        None => admin.clone(),
+    };
+
+    // save INIT_CONFIG state
+    let config = Config {
+        vault_code_id: msg.vault_code_id,
+        vault_creation_fee: msg.vault_creation_fee,
+        unbonding_period: msg.unbonding_period,
+        admin,
+        marketplace_fee,
+        treasury_addr,
+    };
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    // save the vault sequence to storage starting from 0
+    VAULT_SEQ.save(deps.storage, &0u64)?;
+
+    // return response
+    Ok(Response::new().add_attribute("method", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Mint {} => execute_mint(deps, env, info),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_transfer_nft(deps, env, info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => execute_send_nft(deps, env, info, contract, token_id, msg),
+        ExecuteMsg::Approve {
+            spender,
+            token_id,
+            expires,
+        } => execute_approve(deps, env, info, spender, token_id, expires),
+        ExecuteMsg::Revoke { spender, token_id } => {
+            execute_revoke(deps, env, info, spender, token_id)
+        }
+        ExecuteMsg::ApproveAll { operator, expires } => {
+            execute_approve_all(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeAll { operator } => execute_revoke_all(deps, info, operator),
+        ExecuteMsg::ListForSale {
+            vault_id,
+            price,
+            expiration,
+            buyer,
+        } => execute_list_for_sale(deps, env, info, vault_id, price, expiration, buyer),
+        ExecuteMsg::CancelListing { vault_id } => execute_cancel_listing(deps, info, vault_id),
+        ExecuteMsg::Buy { vault_id } => execute_buy(deps, env, info, vault_id),
+        ExecuteMsg::UpdateConfig {
+            vault_code_id,
+            vault_creation_fee,
+            unbonding_period,
+            admin,
+            marketplace_fee,
+            treasury_addr,
+        } => {
+            assert_admin(deps.as_ref(), &info)?;
+            sudo_update_config(
+                deps,
+                vault_code_id,
+                vault_creation_fee,
+                unbonding_period,
+                admin,
+                marketplace_fee,
+                treasury_addr,
+            )
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateConfig {
+            vault_code_id,
+            vault_creation_fee,
+            unbonding_period,
+            admin,
+            marketplace_fee,
+            treasury_addr,
+        } => sudo_update_config(
+            deps,
+            vault_code_id,
+            vault_creation_fee,
+            unbonding_period,
+            admin,
+            marketplace_fee,
+            treasury_addr,
+        ),
+    }
+}
+
+// Make sure the caller is the admin set in the config
+fn assert_admin(deps: Deps, info: &MessageInfo) -> Result<(), ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn sudo_update_config(
+    deps: DepsMut,
+    vault_code_id: Option<u64>,
+    vault_creation_fee: Option<Coin>,
+    unbonding_period: Option<u64>,
+    admin: Option<String>,
+    marketplace_fee: Option<Decimal>,
+    treasury_addr: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = INIT_CONFIG.load(deps.storage)?;
+
+    if let Some(vault_code_id) = vault_code_id {
+        config.vault_code_id = vault_code_id;
+    }
+    if let Some(vault_creation_fee) = vault_creation_fee {
+        if vault_creation_fee.amount.is_zero() {
+            return Err(ContractError::InvalidVaultCreationFee {});
+        }
+        config.vault_creation_fee = vault_creation_fee;
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_validate(&admin)?;
+    }
+    if let Some(marketplace_fee) = marketplace_fee {
+        if marketplace_fee >= Decimal::one() {
+            return Err(ContractError::InvalidMarketplaceFee {});
+        }
+        config.marketplace_fee = marketplace_fee;
+    }
+    if let Some(treasury_addr) = treasury_addr {
+        config.treasury_addr = deps.api.addr_validate(&treasury_addr)?;
+    }
+    INIT_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("method", "sudo_update_config"))
+}
+
+/**
+ * Burn the vault_creation_fee, refund anything sent above it and
+ * instantiate a new vault from config.vault_code_id owned by the caller
+ */
+fn execute_mint(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+
+    // Check if the amount sent by the caller covers the vault_creation_fee
+    let fee = config.vault_creation_fee;
+    let paid = info
+        .funds
+        .iter()
+        .find(|coin| coin.denom == fee.denom && coin.amount >= fee.amount)
+        .ok_or(ContractError::IncorrectVaultCreationFee {})?;
+
+    // Add message to burn the vault_creation_fee
+    let mut msgs: Vec<CosmosMsg> = vec![BankMsg::Burn {
+        amount: vec![fee.clone()],
+    }
+    .into()];
+
+    // Refund the rest of the funds sent
+    let refund: Vec<Coin> = info
+        .funds
+        .iter()
+        .map(|coin| match coin.denom == paid.denom {
+            true => Coin {
+                denom: coin.denom.clone(),
+                amount: coin.amount - fee.amount,
+            },
+            false => coin.clone(),
+        })
+        .filter(|coin| !coin.amount.is_zero())
+        .collect();
+    if !refund.is_empty() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: refund,
+            }
+            .into(),
+        );
+    }
+
+    // Save the owner under a fresh reply id until the vault address is gotten
+    let reply_id = next_reply_seq(deps.storage)?;
+    PENDING_MINTS.save(deps.storage, reply_id, &info.sender)?;
+
+    // Add wasm msg to create new vault instance from config.vault_code_id,
+    // the manager is its wasm admin
+    let sub_msg = SubMsg {
+        gas_limit: None,
+        id: reply_id,
+        reply_on: ReplyOn::Success,
+        msg: WasmMsg::Instantiate {
+            admin: Some(env.contract.address.to_string()),
+            code_id: config.vault_code_id,
+            msg: to_binary(&VaultInstantiateMsg {
+                owner: info.sender.to_string(),
+                unbonding_period: config.unbonding_period,
+            })?,
+            funds: vec![],
+            label: format!("vault-{}", info.sender),
+        }
+        .into(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_mint")
+        .add_messages(msgs)
+        .add_submessage(sub_msg))
+}
+
+/**
+ * Move a vault to recipient, both on the manager record
+ * and on the vault contract itself, cancelling its listing
+ * and approvals. Every change of owner goes through here so
+ * a vault backing an accepted loan never changes hands
+ */
+fn transfer_vault(
+    deps: DepsMut,
+    mut vault: VaultInfo,
+    recipient: Addr,
+) -> Result<CosmosMsg, ContractError> {
+    assert_no_active_loan(deps.as_ref(), &vault)?;
+
+    vault.owner = recipient;
+    vaults().save(deps.storage, vault.id, &vault)?;
+    listings().remove(deps.storage, vault.id)?;
+
+    let spenders = TOKEN_APPROVALS
+        .prefix(vault.id)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for spender in spenders {
+        TOKEN_APPROVALS.remove(deps.storage, (vault.id, &spender));
+    }
+
+    Ok(WasmMsg::Execute {
+        contract_addr: vault.contract_addr.to_string(),
+        msg: to_binary(&VaultExecuteMsg::ChangeOwner {
+            new_owner: vault.owner.to_string(),
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
+// The token_id of a vault is its id
+fn parse_token_id(token_id: &str) -> StdResult<u64> {
+    token_id
+        .parse()
+        .map_err(|_| StdError::generic_err(format!("Invalid token_id: {}", token_id)))
+}
+
+fn load_vault(deps: Deps, token_id: &str) -> Result<VaultInfo, ContractError> {
+    let vault_id = parse_token_id(token_id)?;
+    vaults()
+        .may_load(deps.storage, vault_id)?
+        .ok_or(ContractError::VaultNotFound { vault_id })
+}
+
+// Make sure the caller is the owner of the vault or one of its operators
+fn check_can_approve(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    vault: &VaultInfo,
+) -> Result<(), ContractError> {
+    if info.sender == vault.owner {
+        return Ok(());
+    }
+    match OPERATORS.may_load(deps.storage, (&vault.owner, &info.sender))? {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+// Make sure the caller can approve the vault or is an approved spender
+fn check_can_send(
+    deps: Deps,
+    env: &Env,
+    info: &MessageInfo,
+    vault: &VaultInfo,
+) -> Result<(), ContractError> {
+    match TOKEN_APPROVALS.may_load(deps.storage, (vault.id, &info.sender))? {
+        Some(expires) if !expires.is_expired(&env.block) => Ok(()),
+        _ => check_can_approve(deps, env, info, vault),
+    }
+}
+
+fn execute_transfer_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let vault = load_vault(deps.as_ref(), &token_id)?;
+    check_can_send(deps.as_ref(), &env, &info, &vault)?;
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let msg = transfer_vault(deps, vault, recipient.clone())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_transfer_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("token_id", token_id)
+        .add_message(msg))
+}
+
+/**
+ * Transfer the vault to contract and notify it with ReceiveNft
+ */
+fn execute_send_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let vault = load_vault(deps.as_ref(), &token_id)?;
+    check_can_send(deps.as_ref(), &env, &info, &vault)?;
+
+    let contract = deps.api.addr_validate(&contract)?;
+    let transfer_msg = transfer_vault(deps, vault, contract.clone())?;
+    let receive_msg = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    }
+    .into_cosmos_msg(contract.to_string())?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_send_nft")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", contract)
+        .add_attribute("token_id", token_id)
+        .add_message(transfer_msg)
+        .add_message(receive_msg))
+}
+
+fn execute_approve(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let vault = load_vault(deps.as_ref(), &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info, &vault)?;
+
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let spender = deps.api.addr_validate(&spender)?;
+    TOKEN_APPROVALS.save(deps.storage, (vault.id, &spender), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_approve")
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+fn execute_revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let vault = load_vault(deps.as_ref(), &token_id)?;
+    check_can_approve(deps.as_ref(), &env, &info, &vault)?;
+
+    let spender = deps.api.addr_validate(&spender)?;
+    if !TOKEN_APPROVALS.has(deps.storage, (vault.id, &spender)) {
+        return Err(ContractError::ApprovalNotFound {
+            spender: spender.to_string(),
+        });
+    }
+    TOKEN_APPROVALS.remove(deps.storage, (vault.id, &spender));
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_revoke")
+        .add_attribute("spender", spender)
+        .add_attribute("token_id", token_id))
+}
+
+fn execute_approve_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::Expired {});
+    }
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATORS.save(deps.storage, (&info.sender, &operator), &expires)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_approve_all")
+        .add_attribute("operator", operator))
+}
+
+fn execute_revoke_all(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: String,
+) -> Result<Response, ContractError> {
+    let operator = deps.api.addr_validate(&operator)?;
+    OPERATORS.remove(deps.storage, (&info.sender, &operator));
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_revoke_all")
+        .add_attribute("operator", operator))
+}
+
+// A vault can't be listed or change hands while it is the collateral of an
+// accepted loan, a staking rewards deal follows the vault to its new owner
+fn assert_no_active_loan(deps: Deps, vault: &VaultInfo) -> Result<(), ContractError> {
+    let res: DealResponse = deps
+        .querier
+        .query_wasm_smart(&vault.contract_addr, &VaultQueryMsg::Deal {})?;
+    if matches!(
+        res.deal,
+        Some(Deal {
+            terms: DealTerms::Loan { .. },
+            counterparty: Some(_),
+            ..
+        })
+    ) {
+        return Err(ContractError::ActiveLoan {});
+    }
+    Ok(())
+}
+
+/**
+ * List a vault for sale at a fixed price, optionally until expiration
+ * and only to buyer
+ */
+fn execute_list_for_sale(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    vault_id: u64,
+    price: Coin,
+    expiration: Option<Expiration>,
+    buyer: Option<String>,
+) -> Result<Response, ContractError> {
+    let vault = vaults()
+        .may_load(deps.storage, vault_id)?
+        .ok_or(ContractError::VaultNotFound { vault_id })?;
+    if info.sender != vault.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let expiration = expiration.unwrap_or_default();
+    if price.amount.is_zero() || expiration.is_expired(&env.block) {
+        return Err(ContractError::InvalidListing {});
+    }
+    assert_no_active_loan(deps.as_ref(), &vault)?;
+
+    let listing = Listing {
+        vault_id,
+        seller: vault.owner,
+        price,
+        expiration,
+        buyer: buyer
+            .map(|buyer| deps.api.addr_validate(&buyer))
+            .transpose()?,
+    };
+    listings().save(deps.storage, vault_id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_list_for_sale")
+        .add_attribute("vault_id", vault_id.to_string())
+        .add_attribute("price", listing.price.to_string()))
+}
+
+fn execute_cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    vault_id: u64,
+) -> Result<Response, ContractError> {
+    let listing = listings()
+        .may_load(deps.storage, vault_id)?
+        .ok_or(ContractError::ListingNotFound { vault_id })?;
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+    listings().remove(deps.storage, vault_id)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_cancel_listing")
+        .add_attribute("vault_id", vault_id.to_string()))
+}
+
+/**
+ * Buy a listed vault with exactly its price, the seller gets the price
+ * minus the marketplace_fee sent to the treasury_addr
+ */
+fn execute_buy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    vault_id: u64,
+) -> Result<Response, ContractError> {
+    let config = INIT_CONFIG.load(deps.storage)?;
+    let listing = listings()
+        .may_load(deps.storage, vault_id)?
+        .ok_or(ContractError::ListingNotFound { vault_id })?;
+    let vault = vaults()
+        .may_load(deps.storage, vault_id)?
+        .ok_or(ContractError::VaultNotFound { vault_id })?;
+
+    if info.sender == listing.seller
+        || matches!(&listing.buyer, Some(buyer) if *buyer != info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if listing.expiration.is_expired(&env.block) {
+        return Err(ContractError::ListingExpired {});
+    }
+    if info.funds != [listing.price.clone()] {
+        return Err(ContractError::IncorrectFunds {
+            expected: listing.price.to_string(),
+        });
+    }
+
+    // Split the price between the treasury and the seller
+    let fee = listing.price.amount * config.marketplace_fee;
+    let proceeds = listing.price.amount - fee;
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    if !fee.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: config.treasury_addr.to_string(),
+                amount: vec![coin(fee.u128(), &listing.price.denom)],
+            }
+            .into(),
+        );
+    }
+    if !proceeds.is_zero() {
+        msgs.push(
+            BankMsg::Send {
+                to_address: listing.seller.to_string(),
+                amount: vec![coin(proceeds.u128(), &listing.price.denom)],
+            }
+            .into(),
+        );
+    }
+    msgs.push(transfer_vault(deps, vault, info.sender.clone())?);
+
+    Ok(Response::new()
+        .add_attribute("method", "execute_buy")
+        .add_attribute("vault_id", vault_id.to_string())
+        .add_attribute("buyer", info.sender)
+        .add_messages(msgs))
+}
+
+/**
+ * Handle reply for execute_mint
+ * Load the PENDING_MINTS owner of the reply id and save a new VaultInfo with the newly
+ * created contract_address under the latest VAULT_SEQ id
+ * @return the vault_contract_addr as an attribute on success
+ */
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, msg: Reply) -> StdResult<Response> {
+    match msg.id {
+        id if PENDING_MINTS.has(deps.storage, id) => handle_instantiate_reply(deps, msg),
+        id => Err(StdError::generic_err(format!("Unknown reply id: {}", id))),
+    }
+}
+
+fn handle_instantiate_reply(deps: DepsMut, msg: Reply) -> StdResult<Response> {
+    let reply_id = msg.id;
+
+    // Get data from reply msg
+    // See: https://github.com/CosmWasm/cw-plus/blob/main/packages/utils/src/parse_reply.rs
+    let data = parse_reply_instantiate_data(msg)
+        .map_err(|_| StdError::generic_err("Error parsing data"))?;
+
+    // Get the owner, remove it from the pending mints and get the next id
+    let owner = PENDING_MINTS.load(deps.storage, reply_id)?;
+    PENDING_MINTS.remove(deps.storage, reply_id);
+    let id = next_vault_seq(deps.storage)?;
+
+    // Save the VaultInfo
+    let vault = VaultInfo {
+        id,
+        contract_addr: deps.api.addr_validate(&data.contract_address)?,
+        owner,
+    };
+    vaults().save(deps.storage, id, &vault)?;
+
+    Ok(Response::new()
+        .add_attribute("vault_id", id.to_string())
+        .add_attribute("vault_contract_addr", data.contract_address))
+}
+
+pub fn next_vault_seq(store: &mut dyn Storage) -> StdResult<u64> {
+    let id: u64 = VAULT_SEQ.may_load(store)?.unwrap_or_default() + 1;
+    VAULT_SEQ.save(store, &id)?;
+    Ok(id)
+}
+
+pub fn next_reply_seq(store: &mut dyn Storage) -> StdResult<u64> {
+    let id: u64 = REPLY_SEQ.may_load(store)?.unwrap_or_default() + 1;
+    REPLY_SEQ.save(store, &id)?;
+    Ok(id)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    // Match and route the query message to the appropriate handler
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Vaults { start_after, limit } => {
+            to_binary(&query_vaults(deps, start_after, limit)?)
+        }
+        QueryMsg::Vault { vault_id } => to_binary(&query_vault(deps, vault_id)?),
+        QueryMsg::VaultByAddress { contract_addr } => {
+            to_binary(&query_vault_by_address(deps, contract_addr)?)
+        }
+        QueryMsg::VaultsByOwner {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_vaults_by_owner(deps, owner, start_after, limit)?),
+        QueryMsg::Listings { start_after, limit } => {
+            to_binary(&query_listings(deps, start_after, limit)?)
+        }
+        QueryMsg::Listing { vault_id } => to_binary(&ListingResponse {
+            listing: listings().may_load(deps.storage, vault_id)?,
+        }),
+        QueryMsg::ListingsBySeller {
+            seller,
+            start_after,
+            limit,
+        } => to_binary(&query_listings_by_seller(deps, seller, start_after, limit)?),
+        QueryMsg::OwnerOf {
+            token_id,
+            include_expired,
+        } => to_binary(&query_owner_of(
+            deps,
+            &env,
+            token_id,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approval {
+            token_id,
+            spender,
+            include_expired,
+        } => to_binary(&query_approval(
+            deps,
+            &env,
+            token_id,
+            spender,
+            include_expired.unwrap_or(false),
+        )?),
+        QueryMsg::Approvals {
+            token_id,
+            include_expired,
+        } => to_binary(&ApprovalsResponse {
+            approvals: query_owner_of(deps, &env, token_id, include_expired.unwrap_or(false))?
+                .approvals,
+        }),
+        QueryMsg::AllOperators {
+            owner,
+            include_expired,
+            start_after,
+            limit,
+        } => to_binary(&query_all_operators(
+            deps,
+            &env,
+            owner,
+            include_expired.unwrap_or(false),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NumTokens {} => to_binary(&NumTokensResponse {
+            count: VAULT_SEQ.load(deps.storage)?,
+        }),
+        QueryMsg::ContractInfo {} => to_binary(&ContractInfoResponse {
+            name: NFT_NAME.to_string(),
+            symbol: NFT_SYMBOL.to_string(),
+        }),
+        QueryMsg::NftInfo { token_id } => to_binary(&query_nft_info(deps, token_id)?),
+        QueryMsg::AllNftInfo {
+            token_id,
+            include_expired,
+        } => to_binary(&AllNftInfoResponse {
+            access: query_owner_of(
+                deps,
+                &env,
+                token_id.clone(),
+                include_expired.unwrap_or(false),
+            )?,
+            info: query_nft_info(deps, token_id)?,
+        }),
+        QueryMsg::Tokens {
+            owner,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens(deps, owner, start_after, limit)?),
+        QueryMsg::AllTokens { start_after, limit } => {
+            to_binary(&query_all_tokens(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn query_config(deps: Deps) -> StdResult<Config> {
+    INIT_CONFIG.load(deps.storage)
+}
+
+fn query_vaults(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<VaultsResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let vaults: StdResult<Vec<_>> = vaults()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, vault)| vault))
+        .collect();
+
+    Ok(VaultsResponse { vaults: vaults? })
+}
+
+fn query_vault(deps: Deps, vault_id: u64) -> StdResult<VaultResponse> {
+    let vault = vaults().may_load(deps.storage, vault_id)?;
+    Ok(VaultResponse { vault })
+}
+
+fn query_vault_by_address(deps: Deps, contract_addr: String) -> StdResult<VaultResponse> {
+    let contract_addr = deps.api.addr_validate(&contract_addr)?;
+    let vault = vaults()
+        .idx
+        .contract_addr
+        .item(deps.storage, contract_addr)?
+        .map(|(_, vault)| vault);
+    Ok(VaultResponse { vault })
+}
+
+/**
+ * List the vaults of `owner` ordered by id,
+ * `start_after` is the last id of the previous page
+ */
+fn query_vaults_by_owner(
+    deps: Deps,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<VaultsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let vaults: StdResult<Vec<_>> = vaults()
+        .idx
+        .owner
+        .prefix(owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, vault)| vault))
+        .collect();
+
+    Ok(VaultsResponse { vaults: vaults? })
+}
+
+fn query_listings(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let listings: StdResult<Vec<_>> = listings()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect();
+
+    Ok(ListingsResponse {
+        listings: listings?,
+    })
+}
+
+/**
+ * List the listings of `seller` ordered by vault id,
+ * `start_after` is the last id of the previous page
+ */
+fn query_listings_by_seller(
+    deps: Deps,
+    seller: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let seller = deps.api.addr_validate(&seller)?;
+    let start = start_after.map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let listings: StdResult<Vec<_>> = listings()
+        .idx
+        .seller
+        .prefix(seller)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect();
+
+    Ok(ListingsResponse {
+        listings: listings?,
+    })
+}
+
+// cw721 queries fail on unknown tokens
+fn load_vault_info(deps: Deps, token_id: &str) -> StdResult<VaultInfo> {
+    vaults().load(deps.storage, parse_token_id(token_id)?)
+}
+
+fn query_owner_of(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+    include_expired: bool,
+) -> StdResult<OwnerOfResponse> {
+    let vault = load_vault_info(deps, &token_id)?;
+    let approvals: StdResult<Vec<_>> = TOKEN_APPROVALS
+        .prefix(vault.id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => include_expired || !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .map(|item| {
+            item.map(|(spender, expires)| Approval {
+                spender: spender.to_string(),
+                expires,
+            })
+        })
+        .collect();
+
+    Ok(OwnerOfResponse {
+        owner: vault.owner.to_string(),
+        approvals: approvals?,
+    })
+}
+
+/**
+ * The approval of spender on the vault, the owner is always approved
+ */
+fn query_approval(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+    spender: String,
+    include_expired: bool,
+) -> StdResult<ApprovalResponse> {
+    let vault = load_vault_info(deps, &token_id)?;
+    let spender = deps.api.addr_validate(&spender)?;
+    if spender == vault.owner {
+        return Ok(ApprovalResponse {
+            approval: Approval {
+                spender: spender.to_string(),
+                expires: Expiration::Never {},
+            },
+        });
+    }
+
+    match TOKEN_APPROVALS.may_load(deps.storage, (vault.id, &spender))? {
+        Some(expires) if include_expired || !expires.is_expired(&env.block) => {
+            Ok(ApprovalResponse {
+                approval: Approval {
+                    spender: spender.to_string(),
+                    expires,
+                },
+            })
+        }
+        _ => Err(StdError::not_found("Approval")),
+    }
+}
+
+/**
+ * List the operators of `owner` ordered by address,
+ * `start_after` is the last operator of the previous page
+ */
+fn query_all_operators(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+    include_expired: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<OperatorsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let start_after = start_after
+        .map(|operator| deps.api.addr_validate(&operator))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let operators: StdResult<Vec<_>> = OPERATORS
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => include_expired || !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .take(limit)
+        .map(|item| {
+            item.map(|(operator, expires)| Approval {
+                spender: operator.to_string(),
+                expires,
+            })
+        })
+        .collect();
+
+    Ok(OperatorsResponse {
+        operators: operators?,
+    })
+}
+
+/**
+ * The vault delegations are read from the vault and returned as metadata
+ */
+fn query_nft_info(deps: Deps, token_id: String) -> StdResult<NftInfoResponse> {
+    let vault = load_vault_info(deps, &token_id)?;
+    let res: DelegationsResponse = deps
+        .querier
+        .query_wasm_smart(&vault.contract_addr, &VaultQueryMsg::Delegations {})?;
+    let total_delegated = res
+        .delegations
+        .iter()
+        .map(|delegation| delegation.amount.amount)
+        .sum();
+
+    Ok(NftInfoResponse {
+        token_uri: None,
+        extension: VaultMetadata {
+            vault_addr: vault.contract_addr,
+            total_delegated,
+            delegations: res.delegations,
+        },
+    })
+}
+
+fn query_tokens(
+    deps: Deps,
+    owner: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let tokens: StdResult<Vec<_>> = vaults()
+        .idx
+        .owner
+        .prefix(owner)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|id| id.to_string()))
+        .collect();
+
+    Ok(TokensResponse { tokens: tokens? })
+}
+
+fn query_all_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let start = start_after
+        .map(|token_id| parse_token_id(&token_id))
+        .transpose()?
+        .map(Bound::exclusive);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let tokens: StdResult<Vec<_>> = vaults()
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|id| id.to_string()))
+        .collect();
+
+    Ok(TokensResponse { tokens: tokens? })
+}
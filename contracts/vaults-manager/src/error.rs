@@ -0,0 +1,44 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Incorrect amount sent as vault_creation_fee")]
+    IncorrectVaultCreationFee {},
+
+    #[error("vault_creation_fee must be non zero")]
+    InvalidVaultCreationFee {},
+
+    #[error("Vault with id: {vault_id} does not exist")]
+    VaultNotFound { vault_id: u64 },
+
+    #[error("marketplace_fee must be lower than 1")]
+    InvalidMarketplaceFee {},
+
+    #[error("Vault with id: {vault_id} is not listed for sale")]
+    ListingNotFound { vault_id: u64 },
+
+    #[error("The price must be non zero and the expiration in the future")]
+    InvalidListing {},
+
+    #[error("The listing has expired")]
+    ListingExpired {},
+
+    #[error("Expected exactly {expected}")]
+    IncorrectFunds { expected: String },
+
+    #[error("The vault is collateral of an active loan")]
+    ActiveLoan {},
+
+    #[error("Approval not found for: {spender}")]
+    ApprovalNotFound { spender: String },
+
+    #[error("The approval has expired")]
+    Expired {},
+}
@@ -0,0 +1,453 @@
+#[cfg(test)]
+mod tests {
+    use crate::msg::{
+        ExecuteMsg, InstantiateMsg, ListingResponse, OwnerOfResponse, QueryMsg, VaultsResponse,
+    };
+    use crate::state::VaultInfo;
+    use crate::ContractError;
+
+    use cosmwasm_std::{coin, coins, Addr, Decimal, Empty, Uint128};
+    use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
+    use cw_utils::Expiration;
+    use vault::msg::{ExecuteMsg as VaultExecuteMsg, QueryMsg as VaultQueryMsg};
+    use vault::state::{Action, DealTerms, VaultPreferences};
+    use vault::ContractError as VaultContractError;
+
+    const USER: &str = "user";
+    const OTHER_USER: &str = "other_user";
+    const SELLER_ALT: &str = "seller_alt";
+    const ADMIN: &str = "admin";
+    const NATIVE_DENOM: &str = "udenom";
+    const SUPPLY: u128 = 500_000_000u128;
+
+    // Here we create a struct for instatation config
+    struct InstantiationResponse {
+        app: App,
+        c_addr: Addr,
+        msg: InstantiateMsg,
+    }
+
+    fn contract_vault() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(
+            vault::contract::execute,
+            vault::contract::instantiate,
+            vault::contract::query,
+        ))
+    }
+
+    fn contract_template() -> Box<dyn Contract<Empty>> {
+        Box::new(
+            ContractWrapper::new(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_reply(crate::contract::reply)
+            .with_sudo(crate::contract::sudo),
+        )
+    }
+
+    fn mock_app() -> App {
+        AppBuilder::new().build(|router, _, storage| {
+            router
+                .bank
+                .init_balance(storage, &Addr::unchecked(USER), coins(SUPPLY, NATIVE_DENOM))
+                .unwrap();
+            router
+                .bank
+                .init_balance(
+                    storage,
+                    &Addr::unchecked(OTHER_USER),
+                    coins(SUPPLY, NATIVE_DENOM),
+                )
+                .unwrap();
+        })
+    }
+
+    fn mock_instantiate() -> InstantiationResponse {
+        let mut app = mock_app();
+        let template_id = app.store_code(contract_template());
+        let vault_id = app.store_code(contract_vault());
+
+        let msg = InstantiateMsg {
+            vault_code_id: vault_id,
+            vault_creation_fee: coin(100_000_000u128, NATIVE_DENOM),
+            unbonding_period: 1_209_600,
+            admin: Some(ADMIN.to_string()),
+            marketplace_fee: Some(Decimal::percent(2)),
+            treasury_addr: None,
+        };
+
+        let template_contract_addr = app
+            .instantiate_contract(
+                template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "vaults_manager",
+                None,
+            )
+            .unwrap();
+
+        // return resuable data
+        InstantiationResponse {
+            app,
+            msg,
+            c_addr: template_contract_addr,
+        }
+    }
+
+    fn get_vaults_by_owner(instance: &InstantiationResponse, owner: &str) -> Vec<VaultInfo> {
+        let msg = QueryMsg::VaultsByOwner {
+            owner: owner.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: VaultsResponse = instance
+            .app
+            .wrap()
+            .query_wasm_smart(instance.c_addr.clone(), &msg)
+            .unwrap();
+        res.vaults
+    }
+
+    #[test]
+    fn test_mint_and_transfer_flow() {
+        let mut _instance = mock_instantiate();
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[_instance.msg.vault_creation_fee.clone()],
+            )
+            .unwrap();
+
+        // the vault is recorded for its owner and the fee is burned
+        let vaults = get_vaults_by_owner(&_instance, USER);
+        assert_eq!(
+            vaults,
+            vec![VaultInfo {
+                id: 1,
+                contract_addr: Addr::unchecked("contract1"),
+                owner: Addr::unchecked(USER),
+            }]
+        );
+        let balance = _instance
+            .app
+            .wrap()
+            .query_balance(USER, NATIVE_DENOM)
+            .unwrap();
+        assert_eq!(balance.amount.u128(), SUPPLY - 100_000_000u128);
+
+        let preferences: VaultPreferences = _instance
+            .app
+            .wrap()
+            .query_wasm_smart("contract1", &VaultQueryMsg::Preferences {})
+            .unwrap();
+        assert_eq!(preferences.owner.address, USER);
+        assert_eq!(preferences.vault_manager_address, _instance.c_addr);
+
+        // only the owner can transfer it
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::TransferNft {
+                    recipient: OTHER_USER.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::TransferNft {
+                    recipient: OTHER_USER.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+        // both the manager and the vault know the new owner
+        assert_eq!(get_vaults_by_owner(&_instance, USER), vec![]);
+        assert_eq!(get_vaults_by_owner(&_instance, OTHER_USER).len(), 1);
+        let preferences: VaultPreferences = _instance
+            .app
+            .wrap()
+            .query_wasm_smart("contract1", &VaultQueryMsg::Preferences {})
+            .unwrap();
+        assert_eq!(preferences.owner.address, OTHER_USER);
+    }
+
+    fn balance(instance: &InstantiationResponse, address: &str) -> u128 {
+        instance
+            .app
+            .wrap()
+            .query_balance(address, NATIVE_DENOM)
+            .unwrap()
+            .amount
+            .u128()
+    }
+
+    #[test]
+    fn test_sale_flow() {
+        let mut _instance = mock_instantiate();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[_instance.msg.vault_creation_fee.clone()],
+            )
+            .unwrap();
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::ListForSale {
+                    vault_id: 1,
+                    price: coin(1_000, NATIVE_DENOM),
+                    expiration: None,
+                    buyer: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        // the sale moves the vault and splits the price between the seller and the treasury
+        let seller_balance = balance(&_instance, USER);
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Buy { vault_id: 1 },
+                &coins(1_000, NATIVE_DENOM),
+            )
+            .unwrap();
+        assert_eq!(balance(&_instance, USER), seller_balance + 980);
+        assert_eq!(balance(&_instance, ADMIN), 20);
+        assert_eq!(get_vaults_by_owner(&_instance, OTHER_USER).len(), 1);
+        let preferences: VaultPreferences = _instance
+            .app
+            .wrap()
+            .query_wasm_smart("contract1", &VaultQueryMsg::Preferences {})
+            .unwrap();
+        assert_eq!(preferences.owner.address, OTHER_USER);
+        let res: ListingResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(&_instance.c_addr, &QueryMsg::Listing { vault_id: 1 })
+            .unwrap();
+        assert_eq!(res.listing, None);
+
+        // a vault backing an accepted loan can't be listed
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                Addr::unchecked("contract1"),
+                &VaultExecuteMsg::CreateDeal {
+                    terms: DealTerms::Loan {
+                        amount: coin(500, NATIVE_DENOM),
+                        repayment_amount: Uint128::new(550),
+                        duration: 86_400,
+                        percentage_stake_to_undelegate_at_liquidation: Decimal::percent(50),
+                    },
+                },
+                &[],
+            )
+            .unwrap();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked("contract1"),
+                &VaultExecuteMsg::AcceptDeal {},
+                &coins(500, NATIVE_DENOM),
+            )
+            .unwrap();
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::ListForSale {
+                    vault_id: 1,
+                    price: coin(1_000, NATIVE_DENOM),
+                    expiration: None,
+                    buyer: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<ContractError>().unwrap().to_string(),
+            ContractError::ActiveLoan {}.to_string()
+        );
+    }
+
+    #[test]
+    fn test_sale_removes_seller_controller() {
+        let mut _instance = mock_instantiate();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[_instance.msg.vault_creation_fee.clone()],
+            )
+            .unwrap();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::ListForSale {
+                    vault_id: 1,
+                    price: coin(1_000, NATIVE_DENOM),
+                    expiration: None,
+                    buyer: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+        // the seller grants a second address of theirs a controller that never expires
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                Addr::unchecked("contract1"),
+                &VaultExecuteMsg::SetController {
+                    address: SELLER_ALT.to_string(),
+                    actions_scope: vec![Action::Undelegate, Action::WithdrawFunds],
+                    expiration_date: Expiration::Never {},
+                    percentage_stake_to_undelegate_at_liquidation: Decimal::zero(),
+                },
+                &[],
+            )
+            .unwrap();
+
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(OTHER_USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Buy { vault_id: 1 },
+                &coins(1_000, NATIVE_DENOM),
+            )
+            .unwrap();
+        _instance
+            .app
+            .send_tokens(
+                Addr::unchecked(OTHER_USER),
+                Addr::unchecked("contract1"),
+                &coins(5_000, NATIVE_DENOM),
+            )
+            .unwrap();
+
+        // the controller is gone with the sale and can't drain the buyer's vault
+        let preferences: VaultPreferences = _instance
+            .app
+            .wrap()
+            .query_wasm_smart("contract1", &VaultQueryMsg::Preferences {})
+            .unwrap();
+        assert_eq!(preferences.controller, None);
+        let _err = _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(SELLER_ALT),
+                Addr::unchecked("contract1"),
+                &VaultExecuteMsg::WithdrawFunds {
+                    denom: NATIVE_DENOM.to_string(),
+                    amount: Uint128::new(5_000),
+                    recipient: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            _err.downcast::<VaultContractError>().unwrap().to_string(),
+            VaultContractError::Unauthorized {}.to_string()
+        );
+        assert_eq!(balance(&_instance, "contract1"), 5_000);
+    }
+
+    #[test]
+    fn test_approved_marketplace_transfer() {
+        let mut _instance = mock_instantiate();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[_instance.msg.vault_creation_fee.clone()],
+            )
+            .unwrap();
+
+        // an NFT marketplace approved on the vault moves it like any cw721 token
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked(USER),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::Approve {
+                    spender: "marketplace".to_string(),
+                    token_id: "1".to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+        _instance
+            .app
+            .execute_contract(
+                Addr::unchecked("marketplace"),
+                _instance.c_addr.clone(),
+                &ExecuteMsg::TransferNft {
+                    recipient: OTHER_USER.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+        let res: OwnerOfResponse = _instance
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &_instance.c_addr,
+                &QueryMsg::OwnerOf {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.owner, OTHER_USER);
+        let preferences: VaultPreferences = _instance
+            .app
+            .wrap()
+            .query_wasm_smart("contract1", &VaultQueryMsg::Preferences {})
+            .unwrap();
+        assert_eq!(preferences.owner.address, OTHER_USER);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod contract;
+mod error;
+pub mod integration_test;
+pub mod msg;
+pub mod state;
+pub mod unit_tests;
+
+pub use crate::error::ContractError;
@@ -0,0 +1,288 @@
+use cosmwasm_std::{
+    to_binary, Addr, Binary, Coin, CosmosMsg, Decimal, Delegation, StdResult, Uint128, WasmMsg,
+};
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{Listing, VaultInfo};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub vault_code_id: u64,
+    pub vault_creation_fee: Coin,
+    pub unbonding_period: u64,
+    // defaults to the instantiator
+    pub admin: Option<String>,
+    // defaults to 0
+    pub marketplace_fee: Option<Decimal>,
+    // defaults to the admin
+    pub treasury_addr: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    // Instantiate a new vault owned by the caller
+    Mint {},
+    // cw721 messages, the token_id of a vault is its id. Owner, approved
+    // spender or operator only, hand the vault over to recipient
+    TransferNft {
+        recipient: String,
+        token_id: String,
+    },
+    // Same as TransferNft, calling ReceiveNft on the contract
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+    // Owner or operator only, allow spender to transfer the vault
+    Approve {
+        spender: String,
+        token_id: String,
+        expires: Option<Expiration>,
+    },
+    Revoke {
+        spender: String,
+        token_id: String,
+    },
+    // Allow operator to transfer and approve all the vaults of the caller
+    ApproveAll {
+        operator: String,
+        expires: Option<Expiration>,
+    },
+    RevokeAll {
+        operator: String,
+    },
+    // Owner only, replaces the previous listing of the vault
+    ListForSale {
+        vault_id: u64,
+        price: Coin,
+        // defaults to never
+        expiration: Option<Expiration>,
+        buyer: Option<String>,
+    },
+    // Seller only
+    CancelListing {
+        vault_id: u64,
+    },
+    // With the listing price
+    Buy {
+        vault_id: u64,
+    },
+    // Admin only
+    UpdateConfig {
+        vault_code_id: Option<u64>,
+        vault_creation_fee: Option<Coin>,
+        unbonding_period: Option<u64>,
+        admin: Option<String>,
+        marketplace_fee: Option<Decimal>,
+        treasury_addr: Option<String>,
+    },
+}
+
+// Governance only, same as the admin ExecuteMsg variants
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    UpdateConfig {
+        vault_code_id: Option<u64>,
+        vault_creation_fee: Option<Coin>,
+        unbonding_period: Option<u64>,
+        admin: Option<String>,
+        marketplace_fee: Option<Decimal>,
+        treasury_addr: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    // Paginated by id
+    Vaults {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Vault {
+        vault_id: u64,
+    },
+    VaultByAddress {
+        contract_addr: String,
+    },
+    // Paginated by id, start_after is the last id of the previous page
+    VaultsByOwner {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    // Paginated by vault id
+    Listings {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Listing {
+        vault_id: u64,
+    },
+    // Paginated by vault id, start_after is the last id of the previous page
+    ListingsBySeller {
+        seller: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    // cw721 queries
+    OwnerOf {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    Approval {
+        token_id: String,
+        spender: String,
+        include_expired: Option<bool>,
+    },
+    Approvals {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // Paginated by operator address
+    AllOperators {
+        owner: String,
+        include_expired: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    NumTokens {},
+    ContractInfo {},
+    NftInfo {
+        token_id: String,
+    },
+    AllNftInfo {
+        token_id: String,
+        include_expired: Option<bool>,
+    },
+    // Paginated by token_id
+    Tokens {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    AllTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultsResponse {
+    pub vaults: Vec<VaultInfo>,
+}
+
+// Returned by Vault and VaultByAddress, vault is None when no vault
+// minted through the manager matches
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultResponse {
+    pub vault: Option<VaultInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingResponse {
+    pub listing: Option<Listing>,
+}
+
+// Sent to the contract receiving a vault with SendNft
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct Cw721ReceiveMsg {
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    // Wrap the message as ReceiveNft and execute it on contract_addr
+    pub fn into_cosmos_msg(self, contract_addr: String) -> StdResult<CosmosMsg> {
+        let msg = to_binary(&ReceiverExecuteMsg::ReceiveNft(self))?;
+        Ok(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Approval {
+    pub spender: String,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnerOfResponse {
+    pub owner: String,
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalResponse {
+    pub approval: Approval,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalsResponse {
+    pub approvals: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OperatorsResponse {
+    pub operators: Vec<Approval>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NumTokensResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ContractInfoResponse {
+    pub name: String,
+    pub symbol: String,
+}
+
+// The NFT extension, read from the vault when queried
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultMetadata {
+    pub vault_addr: Addr,
+    pub total_delegated: Uint128,
+    pub delegations: Vec<Delegation>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftInfoResponse {
+    pub token_uri: Option<String>,
+    pub extension: VaultMetadata,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllNftInfoResponse {
+    pub access: OwnerOfResponse,
+    pub info: NftInfoResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<String>,
+}
@@ -0,0 +1,105 @@
+use cosmwasm_std::{Addr, Coin, Decimal};
+
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
+use cw_utils::Expiration;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub vault_code_id: u64,
+    pub vault_creation_fee: Coin,
+    // The chain unbonding period in seconds, passed on to the vaults
+    pub unbonding_period: u64,
+    pub admin: Addr,
+    // Share of the price of every sale sent to treasury_addr
+    pub marketplace_fee: Decimal,
+    pub treasury_addr: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VaultInfo {
+    pub id: u64,
+    pub contract_addr: Addr,
+    pub owner: Addr,
+}
+
+pub struct VaultIndexes<'a> {
+    pub contract_addr: UniqueIndex<'a, Addr, VaultInfo>,
+    // Non unique index that maps an owner to all their vaults,
+    // the primary key (id) is used to paginate within an owner
+    pub owner: MultiIndex<'a, Addr, VaultInfo, u64>,
+}
+
+impl IndexList<VaultInfo> for VaultIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<VaultInfo>> + '_> {
+        let v: Vec<&dyn Index<VaultInfo>> = vec![&self.contract_addr, &self.owner];
+        Box::new(v.into_iter())
+    }
+}
+
+// Here we create an IndexedMap of the vaults keyed by their id
+pub fn vaults<'a>() -> IndexedMap<'a, u64, VaultInfo, VaultIndexes<'a>> {
+    let indexes = VaultIndexes {
+        contract_addr: UniqueIndex::new(|v| v.contract_addr.clone(), "VAULTS_BY_ADDR"),
+        owner: MultiIndex::new(|v| v.owner.clone(), "VAULTS_LIST", "VAULTS_BY_OWNER"),
+    };
+
+    IndexedMap::new("VAULTS_LIST", indexes)
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Listing {
+    pub vault_id: u64,
+    pub seller: Addr,
+    pub price: Coin,
+    pub expiration: Expiration,
+    // Only this address can buy the vault when set
+    pub buyer: Option<Addr>,
+}
+
+pub struct ListingIndexes<'a> {
+    // Non unique index that maps a seller to all their listings
+    pub seller: MultiIndex<'a, Addr, Listing, u64>,
+}
+
+impl IndexList<Listing> for ListingIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.seller];
+        Box::new(v.into_iter())
+    }
+}
+
+// Here we create an IndexedMap of the listings keyed by the vault id
+pub fn listings<'a>() -> IndexedMap<'a, u64, Listing, ListingIndexes<'a>> {
+    let indexes = ListingIndexes {
+        seller: MultiIndex::new(|l| l.seller.clone(), "LISTINGS", "LISTINGS_BY_SELLER"),
+    };
+
+    IndexedMap::new("LISTINGS", indexes)
+}
+
+// The approvals of a vault keyed by vault id and spender,
+// cleared when the vault changes hands
+pub const TOKEN_APPROVALS: Map<(u64, &Addr), Expiration> = Map::new("TOKEN_APPROVALS");
+
+// The operators allowed to transfer and approve all the vaults of an owner
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("OPERATORS");
+
+// This stores the config variables during initialization of the contract
+pub const INIT_CONFIG: Item<Config> = Item::new("INIT_CONFIG");
+
+// This stores the owner of every vault being minted until the contract
+// address is gotten, keyed by the reply id of its instantiate submessage so
+// several mints in the same transaction can't overwrite each other
+pub const PENDING_MINTS: Map<u64, Addr> = Map::new("PENDING_MINTS");
+
+// This keeps track of the number of vaults in the VAULTS_LIST
+pub const VAULT_SEQ: Item<u64> = Item::new("VAULT_SEQ");
+
+// This keeps track of the last reply id handed out to a pending mint
+pub const REPLY_SEQ: Item<u64> = Item::new("REPLY_SEQ");
+
+// Limits for the custom range query
+pub const MAX_LIMIT: u32 = 30;
+pub const DEFAULT_LIMIT: u32 = 10;
@@ -0,0 +1,902 @@
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier};
+    use cosmwasm_std::{
+        coin, coins, from_binary, to_binary, Addr, BankMsg, Binary, ContractResult, CosmosMsg,
+        Decimal, Delegation, Empty, Env, MemoryStorage, OwnedDeps, Reply, ReplyOn, SubMsg,
+        SubMsgResponse, SubMsgResult, SystemResult, Uint128, WasmMsg,
+    };
+    use cw_utils::Expiration;
+
+    use crate::contract::{execute, instantiate, query, reply, sudo};
+    use crate::msg::{
+        Approval, ExecuteMsg, InstantiateMsg, ListingResponse, ListingsResponse, NftInfoResponse,
+        NumTokensResponse, OwnerOfResponse, QueryMsg, SudoMsg, TokensResponse, VaultMetadata,
+        VaultResponse, VaultsResponse,
+    };
+    use crate::state::{Config, Listing, VaultInfo};
+    use crate::ContractError;
+    use vault::msg::{
+        DealResponse, DelegationsResponse, ExecuteMsg as VaultExecuteMsg,
+        InstantiateMsg as VaultInstantiateMsg,
+    };
+    use vault::state::{Deal, DealTerms};
+
+    struct InstantiationResponse {
+        deps: OwnedDeps<MemoryStorage, MockApi, MockQuerier<Empty>, Empty>,
+        caller: String,
+        env: Env,
+        msg: InstantiateMsg,
+    }
+
+    // This function instantiate the contract and returns reusable components
+    fn proper_initialization() -> InstantiationResponse {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let caller = String::from("creator");
+
+        let msg = InstantiateMsg {
+            vault_code_id: 1234u64,
+            vault_creation_fee: coin(100_000_000u128, "udenom"),
+            unbonding_period: 1_209_600,
+            admin: Some(caller.clone()),
+            marketplace_fee: Some(Decimal::percent(2)),
+            treasury_addr: Some("treasury".to_string()),
+        };
+
+        // we can just call .unwrap() to assert this was a success
+        let info = mock_info(&caller, &[]);
+        let _res = instantiate(deps.as_mut(), env.clone(), info, msg.clone()).unwrap();
+        assert_eq!(0, _res.messages.len());
+
+        // query and verify state
+        let res = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                vault_code_id: msg.vault_code_id,
+                vault_creation_fee: msg.vault_creation_fee.clone(),
+                unbonding_period: msg.unbonding_period,
+                admin: Addr::unchecked(&caller),
+                marketplace_fee: Decimal::percent(2),
+                treasury_addr: Addr::unchecked("treasury"),
+            }
+        );
+
+        // return reusable data
+        InstantiationResponse {
+            deps,
+            caller,
+            env,
+            msg,
+        }
+    }
+
+    // Mint a vault for owner and run its instantiate reply
+    fn mint_vault(instance: &mut InstantiationResponse, owner: &str, contract_addr: &str) {
+        let fee = instance.msg.vault_creation_fee.clone();
+        let info = mock_info(owner, &[fee]);
+        let res = execute(
+            instance.deps.as_mut(),
+            instance.env.clone(),
+            info,
+            ExecuteMsg::Mint {},
+        )
+        .unwrap();
+        reply_mint(instance, res.messages.last().unwrap().id, contract_addr);
+        mock_deal(instance, None);
+    }
+
+    // Answer the Deal query of the vaults with deal
+    fn mock_deal(instance: &mut InstantiationResponse, deal: Option<Deal>) {
+        let response = to_binary(&DealResponse {
+            deal,
+            termination_amount: None,
+        })
+        .unwrap();
+        instance
+            .deps
+            .querier
+            .update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(response.clone())));
+    }
+
+    // Run the instantiate reply of a pending mint
+    fn reply_mint(instance: &mut InstantiationResponse, reply_id: u64, contract_addr: &str) {
+        // encode the contract_addr like MsgInstantiateContractResponse
+        let mut data = vec![10, contract_addr.len() as u8];
+        data.extend_from_slice(contract_addr.as_bytes());
+        let reply_msg = Reply {
+            id: reply_id,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: Some(data.into()),
+            }),
+        };
+        reply(instance.deps.as_mut(), instance.env.clone(), reply_msg).unwrap();
+    }
+
+    #[test]
+    fn test_mint() {
+        let mut _instance = proper_initialization();
+
+        // the fee has to be covered
+        let info = mock_info("user", &[coin(1u128, "udenom")]);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            ExecuteMsg::Mint {},
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::IncorrectVaultCreationFee {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // anything above the fee is refunded
+        let info = mock_info("user", &[coin(100_000_010u128, "udenom")]);
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            info,
+            ExecuteMsg::Mint {},
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Burn {
+                amount: vec![_instance.msg.vault_creation_fee.clone()]
+            })
+        );
+        assert_eq!(
+            _res.messages[1].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: vec![coin(10u128, "udenom")]
+            })
+        );
+        assert_eq!(
+            _res.messages[2],
+            SubMsg {
+                id: 1,
+                gas_limit: None,
+                reply_on: ReplyOn::Success,
+                msg: WasmMsg::Instantiate {
+                    admin: Some(_instance.env.contract.address.to_string()),
+                    code_id: _instance.msg.vault_code_id,
+                    msg: to_binary(&VaultInstantiateMsg {
+                        owner: "user".to_string(),
+                        unbonding_period: _instance.msg.unbonding_period,
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                    label: "vault-user".to_string(),
+                }
+                .into()
+            }
+        );
+    }
+
+    #[test]
+    fn test_mint_pending_replies() {
+        let mut _instance = proper_initialization();
+
+        // two mints are pending at the same time, each under its own reply id
+        let fee = _instance.msg.vault_creation_fee.clone();
+        let mut reply_ids = vec![];
+        for owner in ["user", "other_user"] {
+            let res = execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                mock_info(owner, std::slice::from_ref(&fee)),
+                ExecuteMsg::Mint {},
+            )
+            .unwrap();
+            reply_ids.push(res.messages.last().unwrap().id);
+        }
+        assert_eq!(reply_ids, vec![1, 2]);
+
+        // the replies can come back in any order
+        reply_mint(&mut _instance, reply_ids[1], "vault0002");
+        reply_mint(&mut _instance, reply_ids[0], "vault0001");
+
+        for (contract_addr, owner) in [("vault0001", "user"), ("vault0002", "other_user")] {
+            let res = query(
+                _instance.deps.as_ref(),
+                _instance.env.clone(),
+                QueryMsg::VaultByAddress {
+                    contract_addr: contract_addr.to_string(),
+                },
+            )
+            .unwrap();
+            let res: VaultResponse = from_binary(&res).unwrap();
+            assert_eq!(res.vault.unwrap().owner, Addr::unchecked(owner));
+        }
+
+        // a reply id is only handled once
+        let _err = reply(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            Reply {
+                id: reply_ids[0],
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn test_mint_reply_and_queries() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+        mint_vault(&mut _instance, "other_user", "vault0002");
+        mint_vault(&mut _instance, "user", "vault0003");
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::VaultByAddress {
+                contract_addr: "vault0002".to_string(),
+            },
+        )
+        .unwrap();
+        let res: VaultResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.vault,
+            Some(VaultInfo {
+                id: 2,
+                contract_addr: Addr::unchecked("vault0002"),
+                owner: Addr::unchecked("other_user"),
+            })
+        );
+
+        // the vaults of an owner are paginated by id
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::VaultsByOwner {
+                owner: "user".to_string(),
+                start_after: Some(1),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: VaultsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.vaults,
+            vec![VaultInfo {
+                id: 3,
+                contract_addr: Addr::unchecked("vault0003"),
+                owner: Addr::unchecked("user"),
+            }]
+        );
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Vaults {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let res: VaultsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.vaults.iter().map(|v| v.id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_transfer() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+
+        // only the owner can transfer the vault
+        let msg = ExecuteMsg::TransferNft {
+            recipient: "other_user".to_string(),
+            token_id: "1".to_string(),
+        };
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("other_user", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "vault0001".to_string(),
+                msg: to_binary(&VaultExecuteMsg::ChangeOwner {
+                    new_owner: "other_user".to_string()
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Vault { vault_id: 1 },
+        )
+        .unwrap();
+        let res: VaultResponse = from_binary(&res).unwrap();
+        assert_eq!(res.vault.unwrap().owner, Addr::unchecked("other_user"));
+
+        // unknown vaults are reported
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: "other_user".to_string(),
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::VaultNotFound { vault_id: 2 } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // a vault backing an accepted loan can't change hands by any path
+        mock_deal(
+            &mut _instance,
+            Some(Deal {
+                terms: DealTerms::Loan {
+                    amount: coin(500, "udenom"),
+                    repayment_amount: Uint128::new(550),
+                    duration: 86_400,
+                    percentage_stake_to_undelegate_at_liquidation: Decimal::percent(50),
+                },
+                counterparty: Some(Addr::unchecked("lender")),
+                deadline: None,
+                liquidation: None,
+                rewards_paid: Uint128::zero(),
+            }),
+        );
+        let msgs = vec![
+            ExecuteMsg::TransferNft {
+                recipient: "user".to_string(),
+                token_id: "1".to_string(),
+            },
+            ExecuteMsg::SendNft {
+                contract: "marketplace".to_string(),
+                token_id: "1".to_string(),
+                msg: Binary::default(),
+            },
+        ];
+        for msg in msgs {
+            let _err = execute(
+                _instance.deps.as_mut(),
+                _instance.env.clone(),
+                mock_info("other_user", &[]),
+                msg,
+            )
+            .unwrap_err();
+            match _err {
+                ContractError::ActiveLoan {} => {}
+                e => panic!("unexpected error: {}", e),
+            }
+        }
+
+        // an accepted staking rewards deal follows the vault
+        mock_deal(
+            &mut _instance,
+            Some(Deal {
+                terms: DealTerms::StakingRewards {
+                    amount: coin(500, "udenom"),
+                    duration: 86_400,
+                    reward_cap: Uint128::new(100),
+                },
+                counterparty: Some(Addr::unchecked("investor")),
+                deadline: None,
+                liquidation: None,
+                rewards_paid: Uint128::zero(),
+            }),
+        );
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("other_user", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: "user".to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(_res.messages.len(), 1);
+    }
+
+    #[test]
+    fn test_update_config() {
+        let mut _instance = proper_initialization();
+
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::UpdateConfig {
+                vault_code_id: Some(1),
+                vault_creation_fee: None,
+                unbonding_period: None,
+                admin: None,
+                marketplace_fee: None,
+                treasury_addr: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _err = sudo(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            SudoMsg::UpdateConfig {
+                vault_code_id: None,
+                vault_creation_fee: Some(coin(0u128, "udenom")),
+                unbonding_period: None,
+                admin: None,
+                marketplace_fee: None,
+                treasury_addr: None,
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidVaultCreationFee {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info(&_instance.caller, &[]),
+            ExecuteMsg::UpdateConfig {
+                vault_code_id: Some(1),
+                vault_creation_fee: Some(coin(5u128, "uother")),
+                unbonding_period: Some(1_814_400),
+                admin: Some("new_admin".to_string()),
+                marketplace_fee: Some(Decimal::percent(5)),
+                treasury_addr: None,
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Config {},
+        )
+        .unwrap();
+        let config: Config = from_binary(&res).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                vault_code_id: 1,
+                vault_creation_fee: coin(5u128, "uother"),
+                unbonding_period: 1_814_400,
+                admin: Addr::unchecked("new_admin"),
+                marketplace_fee: Decimal::percent(5),
+                treasury_addr: Addr::unchecked("treasury"),
+            }
+        );
+    }
+
+    fn list_msg(price: u128, buyer: Option<&str>) -> ExecuteMsg {
+        ExecuteMsg::ListForSale {
+            vault_id: 1,
+            price: coin(price, "udenom"),
+            expiration: Some(Expiration::AtHeight(mock_env().block.height + 100)),
+            buyer: buyer.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_sale_listing() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("other_user", &[]),
+            list_msg(1_000, None),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            list_msg(0, None),
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::InvalidListing {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            list_msg(1_000, Some("buyer")),
+        )
+        .unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::ListingsBySeller {
+                seller: "user".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: ListingsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res.listings,
+            vec![Listing {
+                vault_id: 1,
+                seller: Addr::unchecked("user"),
+                price: coin(1_000, "udenom"),
+                expiration: Expiration::AtHeight(_instance.env.block.height + 100),
+                buyer: Some(Addr::unchecked("buyer")),
+            }]
+        );
+
+        // only the private buyer can buy, with exactly the price and before expiration
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("other_user", &coins(1_000, "udenom")),
+            ExecuteMsg::Buy { vault_id: 1 },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &coins(900, "udenom")),
+            ExecuteMsg::Buy { vault_id: 1 },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::IncorrectFunds { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+        let mut env = _instance.env.clone();
+        env.block.height += 100;
+        let _err = execute(
+            _instance.deps.as_mut(),
+            env,
+            mock_info("buyer", &coins(1_000, "udenom")),
+            ExecuteMsg::Buy { vault_id: 1 },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ListingExpired {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _res = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &coins(1_000, "udenom")),
+            ExecuteMsg::Buy { vault_id: 1 },
+        )
+        .unwrap();
+        assert_eq!(
+            _res.messages
+                .into_iter()
+                .map(|msg| msg.msg)
+                .collect::<Vec<_>>(),
+            vec![
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "treasury".to_string(),
+                    amount: coins(20, "udenom"),
+                }),
+                CosmosMsg::Bank(BankMsg::Send {
+                    to_address: "user".to_string(),
+                    amount: coins(980, "udenom"),
+                }),
+                CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: "vault0001".to_string(),
+                    msg: to_binary(&VaultExecuteMsg::ChangeOwner {
+                        new_owner: "buyer".to_string()
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                }),
+            ]
+        );
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Listings {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: ListingsResponse = from_binary(&res).unwrap();
+        assert_eq!(res.listings, vec![]);
+    }
+
+    #[test]
+    fn test_transfer_cancels_listing() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            list_msg(1_000, None),
+        )
+        .unwrap();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::TransferNft {
+                recipient: "other_user".to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Listing { vault_id: 1 },
+        )
+        .unwrap();
+        let res: ListingResponse = from_binary(&res).unwrap();
+        assert_eq!(res.listing, None);
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &coins(1_000, "udenom")),
+            ExecuteMsg::Buy { vault_id: 1 },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ListingNotFound { vault_id: 1 } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    fn transfer_nft(
+        instance: &mut InstantiationResponse,
+        sender: &str,
+        recipient: &str,
+    ) -> Result<(), ContractError> {
+        execute(
+            instance.deps.as_mut(),
+            instance.env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .map(|_| ())
+    }
+
+    #[test]
+    fn test_nft_approvals() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+
+        let _err = transfer_nft(&mut _instance, "marketplace", "buyer").unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // an approved spender can transfer the vault once, its approval is then cleared
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::Approve {
+                spender: "marketplace".to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+            },
+        )
+        .unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let res: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            OwnerOfResponse {
+                owner: "user".to_string(),
+                approvals: vec![Approval {
+                    spender: "marketplace".to_string(),
+                    expires: Expiration::Never {},
+                }],
+            }
+        );
+
+        transfer_nft(&mut _instance, "marketplace", "buyer").unwrap();
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::OwnerOf {
+                token_id: "1".to_string(),
+                include_expired: None,
+            },
+        )
+        .unwrap();
+        let res: OwnerOfResponse = from_binary(&res).unwrap();
+        assert_eq!(res.owner, "buyer");
+        assert_eq!(res.approvals, vec![]);
+        let _err = transfer_nft(&mut _instance, "marketplace", "user").unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        // an operator can move every vault of the owner until it is revoked
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &[]),
+            ExecuteMsg::ApproveAll {
+                operator: "operator".to_string(),
+                expires: Some(Expiration::AtHeight(_instance.env.block.height + 10)),
+            },
+        )
+        .unwrap();
+        transfer_nft(&mut _instance, "operator", "buyer").unwrap();
+        execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &[]),
+            ExecuteMsg::RevokeAll {
+                operator: "operator".to_string(),
+            },
+        )
+        .unwrap();
+        let _err = transfer_nft(&mut _instance, "operator", "user").unwrap_err();
+        match _err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {}", e),
+        }
+
+        let _err = execute(
+            _instance.deps.as_mut(),
+            _instance.env.clone(),
+            mock_info("buyer", &[]),
+            ExecuteMsg::Revoke {
+                spender: "marketplace".to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        match _err {
+            ContractError::ApprovalNotFound { .. } => {}
+            e => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_nft_queries() {
+        let mut _instance = proper_initialization();
+        mint_vault(&mut _instance, "user", "vault0001");
+        mint_vault(&mut _instance, "other_user", "vault0002");
+        mint_vault(&mut _instance, "user", "vault0003");
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::NumTokens {},
+        )
+        .unwrap();
+        let res: NumTokensResponse = from_binary(&res).unwrap();
+        assert_eq!(res.count, 3);
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::Tokens {
+                owner: "user".to_string(),
+                start_after: Some("1".to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let res: TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(res.tokens, vec!["3".to_string()]);
+
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::AllTokens {
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap();
+        let res: TokensResponse = from_binary(&res).unwrap();
+        assert_eq!(res.tokens, vec!["1".to_string(), "2".to_string()]);
+
+        // the metadata shows the vault delegations
+        let delegations = vec![
+            Delegation {
+                delegator: Addr::unchecked("vault0001"),
+                validator: "validator1".to_string(),
+                amount: coin(1_000, "ustake"),
+            },
+            Delegation {
+                delegator: Addr::unchecked("vault0001"),
+                validator: "validator2".to_string(),
+                amount: coin(500, "ustake"),
+            },
+        ];
+        let response = to_binary(&DelegationsResponse {
+            delegations: delegations.clone(),
+        })
+        .unwrap();
+        _instance
+            .deps
+            .querier
+            .update_wasm(move |_| SystemResult::Ok(ContractResult::Ok(response.clone())));
+        let res = query(
+            _instance.deps.as_ref(),
+            _instance.env.clone(),
+            QueryMsg::NftInfo {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let res: NftInfoResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            res,
+            NftInfoResponse {
+                token_uri: None,
+                extension: VaultMetadata {
+                    vault_addr: Addr::unchecked("vault0001"),
+                    total_delegated: 1_500u128.into(),
+                    delegations,
+                },
+            }
+        );
+    }
+}
@@ -0,0 +1,79 @@
+/*!
+ * Minimal protobuf wire encoding for the Stargate messages sent by the
+ * contracts and their responses, cosmwasm-std 1.1 has no typed message for them
+ */
+
+// Append value as a base 128 varint
+pub fn encode_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+// Append a varint protobuf field, such as a uint64 or an enum
+pub fn encode_varint_field(buf: &mut Vec<u8>, field_number: u64, value: u64) {
+    encode_varint(buf, field_number << 3);
+    encode_varint(buf, value);
+}
+
+// Append a length delimited protobuf field, such as a string or an embedded message
+pub fn encode_bytes_field(buf: &mut Vec<u8>, field_number: u64, bytes: &[u8]) {
+    encode_varint(buf, field_number << 3 | 2);
+    encode_varint(buf, bytes.len() as u64);
+    buf.extend_from_slice(bytes);
+}
+
+// Read a base 128 varint from the start of bytes, with the number of bytes it takes
+pub fn decode_varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_varint() {
+        let mut buf = vec![];
+        encode_varint(&mut buf, 1);
+        encode_varint(&mut buf, 300);
+        encode_varint(&mut buf, u64::MAX);
+        assert_eq!(
+            buf,
+            vec![0x01, 0xac, 0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_decode_varint() {
+        assert_eq!(decode_varint(&[0x01]), Some((1, 1)));
+        assert_eq!(decode_varint(&[0xac, 0x02, 0x08]), Some((300, 2)));
+        let mut buf = vec![];
+        encode_varint(&mut buf, u64::MAX);
+        assert_eq!(decode_varint(&buf), Some((u64::MAX, 10)));
+
+        // truncated
+        assert_eq!(decode_varint(&[0xac]), None);
+        assert_eq!(decode_varint(&[]), None);
+    }
+
+    #[test]
+    fn test_encode_fields() {
+        let mut buf = vec![];
+        encode_varint_field(&mut buf, 1, 150);
+        encode_bytes_field(&mut buf, 2, b"testing");
+        assert_eq!(
+            buf,
+            vec![0x08, 0x96, 0x01, 0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g']
+        );
+    }
+}